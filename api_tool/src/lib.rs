@@ -4,5 +4,5 @@ pub mod api_tool;
 
 #[put("/lib/{id}")]
 pub async fn lib(id: web::Path<String>) -> HttpResponse {
-    HttpResponse::Ok().body(format!("Updated user: {}", id.to_string()))
+    HttpResponse::Ok().body(format!("Updated user: {}", id))
 }
@@ -0,0 +1,355 @@
+//! `cargo routes`：离线扫描 crate 及其 path 依赖（在虚拟 workspace 根目录运行时扫描所有成员），列出、过滤并导出最终的路由表
+//!
+//! ```text
+//! cargo routes [--manifest-path <DIR|Cargo.toml>] [--method GET] [--crate api_tool]
+//!              [--module handler::agency] [--path "/handler/**"]
+//!              [--format table|json|csv|markdown] [--strict]
+//! ```
+
+use globset::{Glob, GlobMatcher};
+use route_codegen_build::{find_invocations, Generator, Invocation, RouteEntry};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: cargo routes [OPTIONS]
+
+Options:
+  --manifest-path <PATH>  Crate directory or its Cargo.toml (default: current directory)
+  --name <NAME>           Use the generate_configure! invocation with this name (default: configure)
+  --method <METHOD>       Only show routes with this HTTP method
+  --crate <NAME>          Only show handlers defined in this crate
+  --module <PATH>         Only show handlers in this module or its submodules
+  --path <GLOB>           Only show routes whose full path matches the glob; `*` and `**` are
+                          wildcards, `{..}` matches path templates literally (e.g. /users/{id})
+  --format <FORMAT>       Output format: table (default), json, csv, markdown
+  --strict                Fail on unreadable files, parse errors or unresolved workspace members
+  -h, --help              Print this help";
+
+/// 输出格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Table,
+    Json,
+    Csv,
+    Markdown,
+}
+
+/// 命令行参数
+#[derive(Debug)]
+struct Options {
+    manifest_dir: PathBuf,
+    name: Option<String>,
+    method: Option<String>,
+    crate_name: Option<String>,
+    module: Option<String>,
+    path: Option<GlobMatcher>,
+    format: Format,
+    strict: bool,
+}
+
+fn main() -> ExitCode {
+    // 作为 cargo 子命令调用时，第一个参数是子命令名 "routes"
+    let args: Vec<String> = std::env::args()
+        .skip(1)
+        .enumerate()
+        .filter(|(index, arg)| !(*index == 0 && arg == "routes"))
+        .map(|(_, arg)| arg)
+        .collect();
+
+    let options = match parse_args(&args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let invocation = match select_invocation(&options) {
+        Ok(invocation) => invocation,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut generator = Generator::new().manifest_dir(&options.manifest_dir);
+    if let Some(invocation) = invocation {
+        generator = invocation
+            .args
+            .apply(generator.invoked_from(invocation.file));
+    }
+    if options.strict {
+        generator = generator.strict(true);
+    }
+
+    let routes = match generator.quiet().routes() {
+        Ok(routes) => routes,
+        Err(e) => {
+            eprintln!("error: failed to scan crate for route functions: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let routes: Vec<RouteEntry> = routes
+        .into_iter()
+        .filter(|route| options.matches(route))
+        .collect();
+
+    let output = match options.format {
+        Format::Table => render_table(&routes),
+        Format::Json => render_json(&routes),
+        Format::Csv => render_csv(&routes),
+        Format::Markdown => render_markdown(&routes),
+    };
+    print!("{}", output);
+    ExitCode::SUCCESS
+}
+
+/// 解析命令行参数；`--help` 返回 `Ok(None)`
+fn parse_args(args: &[String]) -> Result<Option<Options>, String> {
+    let mut options = Options {
+        manifest_dir: std::env::current_dir().map_err(|e| e.to_string())?,
+        name: None,
+        method: None,
+        crate_name: None,
+        module: None,
+        path: None,
+        format: Format::Table,
+        strict: false,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        // 同时支持 --key value 和 --key=value
+        let (key, inline_value) = match arg.split_once('=') {
+            Some((key, value)) if key.starts_with("--") => (key, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        match key {
+            "-h" | "--help" => return Ok(None),
+            "--strict" => {
+                options.strict = true;
+                continue;
+            }
+            _ => {}
+        }
+
+        let value = match inline_value {
+            Some(value) => value,
+            None => args
+                .next()
+                .cloned()
+                .ok_or_else(|| format!("missing value for `{}`", key))?,
+        };
+
+        match key {
+            "--manifest-path" => {
+                let path = PathBuf::from(value);
+                options.manifest_dir = if path.file_name().is_some_and(|n| n == "Cargo.toml") {
+                    path.parent().map(PathBuf::from).unwrap_or_default()
+                } else {
+                    path
+                };
+            }
+            "--name" => options.name = Some(value),
+            "--method" => options.method = Some(value.to_uppercase()),
+            "--crate" => options.crate_name = Some(value.replace('-', "_")),
+            "--module" => options.module = Some(value.trim_matches(':').to_string()),
+            "--path" => {
+                // 路由路径中的 {id} 是模板，不是 glob 的多选语法
+                let value = value.replace('{', "[{]").replace('}', "[}]");
+                let glob = Glob::new(&value).map_err(|e| format!("invalid path glob: {}", e))?;
+                options.path = Some(glob.compile_matcher());
+            }
+            "--format" => {
+                options.format = match value.as_str() {
+                    "table" => Format::Table,
+                    "json" => Format::Json,
+                    "csv" => Format::Csv,
+                    "markdown" | "md" => Format::Markdown,
+                    _ => return Err(format!("unknown format `{}`", value)),
+                }
+            }
+            _ => return Err(format!("unknown option `{}`", key)),
+        }
+    }
+
+    // 相对路径的文件名在 strip_prefix 时需要与扫描结果一致
+    options.manifest_dir = options
+        .manifest_dir
+        .canonicalize()
+        .map_err(|e| format!("{}: {}", options.manifest_dir.display(), e))?;
+    Ok(Some(options))
+}
+
+/// 选择用于计算路由表的 `generate_configure!` 调用
+///
+/// 指定 `--name` 时使用同名的调用；否则只有一个调用时使用它，有多个调用时使用名为
+/// `configure` 的调用（没有时使用第一个）并给出警告。源码中没有调用时使用默认选项。
+fn select_invocation(options: &Options) -> Result<Option<Invocation>, String> {
+    let invocations = find_invocations(&options.manifest_dir)?;
+    if let Some(name) = &options.name {
+        return invocations
+            .into_iter()
+            .find(|invocation| invocation.args.name() == name)
+            .map(Some)
+            .ok_or_else(|| {
+                format!(
+                    "no generate_configure!(name = {}, ..) invocation found",
+                    name
+                )
+            });
+    }
+    if invocations.len() > 1 {
+        let names: Vec<&str> = invocations
+            .iter()
+            .map(|invocation| invocation.args.name())
+            .collect();
+        let selected = invocations
+            .iter()
+            .position(|invocation| invocation.args.name() == "configure")
+            .unwrap_or(0);
+        eprintln!(
+            "warning: found {} generate_configure! invocations ({}); listing `{}`, select another with --name",
+            invocations.len(),
+            names.join(", "),
+            names[selected]
+        );
+        return Ok(invocations.into_iter().nth(selected));
+    }
+    Ok(invocations.into_iter().next())
+}
+
+impl Options {
+    /// 判断路由是否满足所有过滤条件
+    fn matches(&self, route: &RouteEntry) -> bool {
+        let module_matches = |module: &str| {
+            module == route.module
+                || route
+                    .module
+                    .strip_prefix(module)
+                    .is_some_and(|rest| rest.starts_with("::"))
+        };
+
+        self.method.as_ref().is_none_or(|m| *m == route.method)
+            && self
+                .crate_name
+                .as_ref()
+                .is_none_or(|c| *c == route.crate_name)
+            && self.module.as_deref().is_none_or(module_matches)
+            && self
+                .path
+                .as_ref()
+                .is_none_or(|glob| glob.is_match(&route.path))
+    }
+}
+
+/// 表格中一行的各列取值（与 HEADERS 对应）
+fn columns(route: &RouteEntry) -> [String; 4] {
+    [
+        route.method.clone(),
+        route.path.clone(),
+        route.handler.clone(),
+        format!("{}:{}", route.file, route.line),
+    ]
+}
+
+const HEADERS: [&str; 4] = ["METHOD", "PATH", "HANDLER", "FILE"];
+
+/// 对齐的纯文本表格
+fn render_table(routes: &[RouteEntry]) -> String {
+    let rows: Vec<[String; 4]> = routes.iter().map(columns).collect();
+    let mut widths = HEADERS.map(|header| header.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut output = String::new();
+    let header = HEADERS.map(String::from);
+    for row in std::iter::once(&header).chain(&rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        output.push_str(line.join("  ").trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+/// JSON 数组，字段与 `route_codegen_runtime::RouteInfo` 一致
+fn render_json(routes: &[RouteEntry]) -> String {
+    let routes: Vec<serde_json::Value> = routes
+        .iter()
+        .map(|route| {
+            serde_json::json!({
+                "method": route.method,
+                "path": route.path,
+                "handler": route.handler,
+                "crate_name": route.crate_name,
+                "module": route.module,
+                "file": route.file,
+                "line": route.line,
+                "path_params": route.path_params,
+            })
+        })
+        .collect();
+    let mut output = serde_json::to_string_pretty(&routes).unwrap_or_default();
+    output.push('\n');
+    output
+}
+
+/// RFC 4180 CSV
+fn render_csv(routes: &[RouteEntry]) -> String {
+    let escape = |value: &str| {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    };
+
+    let mut output = String::from("method,path,handler,crate_name,module,file,line\n");
+    for route in routes {
+        let line = route.line.to_string();
+        let row = [
+            &route.method,
+            &route.path,
+            &route.handler,
+            &route.crate_name,
+            &route.module,
+            &route.file,
+            &line,
+        ];
+        let row: Vec<String> = row.iter().map(|value| escape(value)).collect();
+        output.push_str(&row.join(","));
+        output.push('\n');
+    }
+    output
+}
+
+/// Markdown 表格
+fn render_markdown(routes: &[RouteEntry]) -> String {
+    let escape = |value: &str| value.replace('|', "\\|");
+
+    let mut output =
+        String::from("| Method | Path | Handler | File |\n| --- | --- | --- | --- |\n");
+    for route in routes {
+        let [method, path, handler, file] = columns(route);
+        output.push_str(&format!(
+            "| {} | `{}` | `{}` | {} |\n",
+            method,
+            escape(&path),
+            escape(&handler),
+            escape(&file)
+        ));
+    }
+    output
+}
@@ -0,0 +1,43 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 计算 route_codegen_build 自身源码的指纹，写入 `ROUTE_CODEGEN_BUILD_FINGERPRINT`
+///
+/// 扫描缓存的版本包含这个指纹：提取规则改动后即使没有递增版本号，旧缓存也会失效。
+fn main() {
+    let manifest_dir = PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    let mut files = vec![manifest_dir.join("Cargo.toml")];
+    collect_files(&manifest_dir.join("src"), &mut files);
+    files.sort();
+
+    // 64 位 FNV-1a，与 tools::stable_hash 相同
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for file in &files {
+        let relative = file.strip_prefix(&manifest_dir).unwrap_or(file);
+        let content = fs::read(file).unwrap_or_default();
+        for &byte in relative.to_string_lossy().as_bytes().iter().chain(&content) {
+            hash = (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!("cargo:rerun-if-changed=src");
+    println!(
+        "cargo:rustc-env=ROUTE_CODEGEN_BUILD_FINGERPRINT={:016x}",
+        hash
+    );
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use syn::{Attribute, Ident, Item, LitInt, LitStr};
+
+/// 判断属性是否是 route_codegen 自身的标记属性，支持 #[xxx] 和 #[route_codegen::xxx]
+pub fn is_codegen_attribute(attr: &Attribute, name: &str) -> bool {
+    is_codegen_path(attr.path(), name)
+}
+
+/// 判断路径是否是 xxx 或 route_codegen::xxx
+fn is_codegen_path(path: &syn::Path, name: &str) -> bool {
+    let segments: Vec<_> = path.segments.iter().collect();
+    match segments.as_slice() {
+        [ident] => ident.ident == name,
+        [krate, ident] => krate.ident == "route_codegen" && ident.ident == name,
+        _ => false,
+    }
+}
+
+/// 读取 #[route_codegen::priority(n)]，未标注时为 0
+pub fn parse_priority(attrs: &[Attribute]) -> syn::Result<i32> {
+    match attrs
+        .iter()
+        .find(|attr| is_codegen_attribute(attr, "priority"))
+    {
+        Some(attr) => attr.parse_args::<LitInt>()?.base10_parse::<i32>(),
+        None => Ok(0),
+    }
+}
+
+/// 是否标注了 #[route_codegen::skip]，标注后 handler 不参与自动注册
+pub fn is_skipped(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| is_codegen_attribute(attr, "skip"))
+}
+
+/// 读取条目上的 #[cfg(..)] 条件（如 `feature = "beta"`）
+///
+/// 过程宏无法得知当前启用的 feature 和目标平台，这些条件会原样复制到生成的注册代码上，
+/// 由编译器决定是否注册对应的 handler。
+pub fn parse_cfgs(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+        .filter_map(|attr| attr.meta.require_list().ok())
+        .map(|list| list.tokens.to_string())
+        .collect()
+}
+
+/// 生成代码上的 `#[cfg(..)]`，多个条件用 `all(..)` 合并，没有条件时为空
+pub fn cfg_attribute(cfgs: &[String]) -> proc_macro2::TokenStream {
+    let predicates: Vec<proc_macro2::TokenStream> =
+        cfgs.iter().filter_map(|cfg| cfg.parse().ok()).collect();
+    match predicates.as_slice() {
+        [] => quote::quote! {},
+        [predicate] => quote::quote! { #[cfg(#predicate)] },
+        _ => quote::quote! { #[cfg(all(#(#predicates),*))] },
+    }
+}
+
+/// 模块的 scope 声明：`#[route_scope("/v1/agencies")]` / `#[route_scope(flat)]`，
+/// 或模块文件中的 `route_codegen::module_scope!(..)`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RouteScope {
+    /// 使用指定的前缀代替模块路径推导出的前缀
+    Path(String),
+    /// 不使用模块路径推导的前缀，handler 直接按自身的路径注册
+    Flat,
+}
+
+impl syn::parse::Parse for RouteScope {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let scope = if input.peek(LitStr) {
+            let lit: LitStr = input.parse()?;
+            let path = lit.value();
+            if !path.starts_with('/') {
+                return Err(syn::Error::new_spanned(
+                    lit,
+                    "route scope must start with `/`; use `flat` to register handlers without a scope",
+                ));
+            }
+            RouteScope::Path(path.trim_end_matches('/').to_string())
+        } else {
+            let ident: Ident = input.parse()?;
+            if ident != "flat" {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "expected a path like \"/v1/agencies\" or `flat`",
+                ));
+            }
+            RouteScope::Flat
+        };
+        if !input.is_empty() {
+            return Err(input.error("unexpected tokens after route scope"));
+        }
+        Ok(scope)
+    }
+}
+
+/// 模块树中最近一次声明的 scope：`depth` 为声明所在模块的路径段数，
+/// 更深的子模块在 `scope` 后面追加相对的模块路径
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScopeAnchor {
+    pub scope: String, // 声明的前缀（flat 为空字符串）
+    pub depth: usize,  // 声明所在模块的路径段数（不含 crate）
+}
+
+/// 读取模块上的 scope 声明：属性（内部或外部）以及 module_scope! 宏，声明多次时报错
+pub fn parse_route_scope(attrs: &[Attribute], items: &[Item]) -> syn::Result<Option<RouteScope>> {
+    let mut declarations = Vec::new();
+    for attr in attrs
+        .iter()
+        .filter(|attr| is_codegen_attribute(attr, "route_scope"))
+    {
+        declarations.push((attr.parse_args::<RouteScope>()?, attr.path().clone()));
+    }
+    for item in items {
+        if let Item::Macro(item) = item
+            && is_codegen_path(&item.mac.path, "module_scope")
+        {
+            declarations.push((item.mac.parse_body::<RouteScope>()?, item.mac.path.clone()));
+        }
+    }
+
+    match declarations.as_slice() {
+        [] => Ok(None),
+        [(scope, _)] => Ok(Some(scope.clone())),
+        [_, (_, path), ..] => Err(syn::Error::new_spanned(
+            path,
+            "route scope is declared more than once for this module",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copies_cfg_conditions() {
+        let item: syn::ItemFn =
+            syn::parse_str(r#"#[cfg(feature = "beta")] #[cfg(not(test))] #[doc = "x"] fn f() {}"#)
+                .unwrap();
+        let cfgs = parse_cfgs(&item.attrs);
+        assert_eq!(cfgs, [r#"feature = "beta""#, "not (test)"]);
+        assert_eq!(
+            cfg_attribute(&cfgs).to_string(),
+            quote::quote! { #[cfg(all(feature = "beta", not(test)))] }.to_string()
+        );
+        assert_eq!(
+            cfg_attribute(&cfgs[..1]).to_string(),
+            quote::quote! { #[cfg(feature = "beta")] }.to_string()
+        );
+        assert!(cfg_attribute(&[]).is_empty());
+    }
+}
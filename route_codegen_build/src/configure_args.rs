@@ -0,0 +1,413 @@
+use crate::configure_builder::ScopeStrategy;
+use crate::generator::Generator;
+use crate::registration::Registration;
+use crate::tools::collect_rs_files;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use syn::visit::Visit;
+use syn::{braced, bracketed, Ident, LitBool, LitStr, Token};
+
+/// `generate_configure!` 的参数：glob 字符串列表和 `strict`，或 key = value 形式的选项
+///
+/// 解析规则只有这一份，过程宏和其他前端（如 `cargo routes`）读取同一次调用时得到相同的选项。
+#[derive(Clone, Debug, Default)]
+pub struct ConfigureArgs {
+    name: Option<String>,               // 入口函数名（name = configure_public）
+    patterns: Vec<String>,              // glob 规则，排除规则以 ! 开头
+    scope: Option<ScopeStrategy>,       // scope 策略（scope = "flat" | "module" | "nested"）
+    prefix: Option<String>,             // 公共前缀（prefix = "/api"）
+    strict: bool,                       // 扫描问题报告为编译错误（strict / strict = true）
+    strip: Option<Vec<String>>,         // 去掉的开头模块（strip = ["handler", "api"]）
+    collapse_duplicates: Option<bool>,  // 合并重复的相邻模块段（collapse_duplicates = true）
+    kebab_case: Option<bool>,           // 模块名转换为 kebab-case（kebab_case = true）
+    mount: Vec<(String, String)>,       // 依赖 crate 的挂载前缀（mount = { api_tool = "/tools" }）
+    cache: Option<bool>,                // 是否使用 target 目录中的扫描缓存（cache = false）
+    registration: Option<Registration>, // 路由的登记方式（registration = "scan" | "static"）
+    external_data: Vec<String>,         // 手写代码注册的 app data（external_data = ["AppConfig"]）
+}
+
+impl syn::parse::Parse for ConfigureArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut args = ConfigureArgs::default();
+        let mut seen = BTreeSet::new();
+        while !input.is_empty() {
+            if input.peek(LitStr) {
+                let path: LitStr = input.parse()?;
+                args.patterns.push(path.value());
+            } else {
+                let key: Ident = input.parse()?;
+                if !seen.insert(key.to_string()) {
+                    return Err(syn::Error::new_spanned(
+                        &key,
+                        format!("duplicate option `{}`", key),
+                    ));
+                }
+                if key == "strict" && !input.peek(Token![=]) {
+                    args.strict = true;
+                } else {
+                    input.parse::<Token![=]>()?;
+                    args.parse_option(&key, input)?;
+                }
+            }
+            if !input.is_empty() {
+                let _: syn::Token![,] = input.parse()?;
+            }
+        }
+        Ok(args)
+    }
+}
+
+impl ConfigureArgs {
+    /// 生成的入口函数名
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or("configure")
+    }
+
+    /// 解析 key = value 中的 value
+    fn parse_option(&mut self, key: &Ident, input: syn::parse::ParseStream) -> syn::Result<()> {
+        match key.to_string().as_str() {
+            "name" => {
+                let name = if input.peek(LitStr) {
+                    let lit: LitStr = input.parse()?;
+                    lit.parse::<Ident>()?
+                } else {
+                    input.parse::<Ident>()?
+                };
+                self.name = Some(name.to_string());
+            }
+            "include" => self.patterns.extend(parse_patterns(input)?),
+            "exclude" => self.patterns.extend(
+                parse_patterns(input)?
+                    .into_iter()
+                    .map(|pattern| format!("!{}", pattern.trim_start_matches('!'))),
+            ),
+            "scope" => {
+                let lit: LitStr = input.parse()?;
+                let scope = lit
+                    .value()
+                    .parse()
+                    .map_err(|e| syn::Error::new_spanned(&lit, e))?;
+                self.scope = Some(scope);
+            }
+            "prefix" => {
+                let lit: LitStr = input.parse()?;
+                let prefix = lit.value();
+                if !prefix.is_empty() && !prefix.starts_with('/') {
+                    return Err(syn::Error::new_spanned(lit, "prefix must start with `/`"));
+                }
+                self.prefix = Some(prefix);
+            }
+            "strict" => self.strict = input.parse::<LitBool>()?.value,
+            "strip" => self.strip = Some(parse_patterns(input)?),
+            "collapse_duplicates" => {
+                self.collapse_duplicates = Some(input.parse::<LitBool>()?.value)
+            }
+            "kebab_case" => self.kebab_case = Some(input.parse::<LitBool>()?.value),
+            "mount" => self.mount = parse_mounts(input)?,
+            "cache" => self.cache = Some(input.parse::<LitBool>()?.value),
+            "registration" => {
+                let lit: LitStr = input.parse()?;
+                let registration = lit
+                    .value()
+                    .parse()
+                    .map_err(|e| syn::Error::new_spanned(&lit, e))?;
+                self.registration = Some(registration);
+            }
+            "external_data" => self.external_data = parse_patterns(input)?,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "unknown option; allowed: name, include, exclude, scope, prefix, strict, \
+                     strip, collapse_duplicates, kebab_case, mount, cache, registration and external_data",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 把参数应用到生成器上
+    pub fn apply(self, mut generator: Generator) -> Generator {
+        for pattern in self.patterns {
+            generator = generator.include(pattern);
+        }
+        if let Some(name) = self.name {
+            generator = generator.name(name);
+        }
+        if let Some(scope) = self.scope {
+            generator = generator.scope(scope);
+        }
+        if let Some(prefix) = self.prefix {
+            generator = generator.prefix(prefix);
+        }
+        generator = generator.strict(self.strict);
+        for module in self.strip.iter().flatten() {
+            generator = generator.strip(module);
+        }
+        if let Some(collapse) = self.collapse_duplicates {
+            generator = generator.collapse_duplicates(collapse);
+        }
+        if let Some(kebab_case) = self.kebab_case {
+            generator = generator.kebab_case(kebab_case);
+        }
+        for (crate_name, prefix) in self.mount {
+            generator = generator.mount(crate_name, prefix);
+        }
+        if let Some(cache) = self.cache {
+            generator = generator.cache(cache);
+        }
+        if let Some(registration) = self.registration {
+            generator = generator.registration(registration);
+        }
+        for ty in self.external_data {
+            generator = generator.external_data(ty);
+        }
+        generator
+    }
+}
+
+/// 解析单个 glob 字符串或 ["..", ".."] 列表
+fn parse_patterns(input: syn::parse::ParseStream) -> syn::Result<Vec<String>> {
+    if input.peek(LitStr) {
+        let lit: LitStr = input.parse()?;
+        return Ok(vec![lit.value()]);
+    }
+
+    let content;
+    bracketed!(content in input);
+    let patterns = content.parse_terminated(|input| input.parse::<LitStr>(), Token![,])?;
+    Ok(patterns.iter().map(|lit| lit.value()).collect())
+}
+
+/// 解析 { crate_name = "/prefix", .. }，crate 名称可以是标识符或字符串
+fn parse_mounts(input: syn::parse::ParseStream) -> syn::Result<Vec<(String, String)>> {
+    let content;
+    braced!(content in input);
+    let mounts = content.parse_terminated(
+        |input| {
+            let crate_name = if input.peek(LitStr) {
+                input.parse::<LitStr>()?.value()
+            } else {
+                input.parse::<Ident>()?.to_string()
+            };
+            input.parse::<Token![=]>()?;
+            let prefix: LitStr = input.parse()?;
+            if !prefix.value().is_empty() && !prefix.value().starts_with('/') {
+                return Err(syn::Error::new_spanned(
+                    prefix,
+                    "mount prefix must start with `/`, or be \"\" for the root",
+                ));
+            }
+            Ok((crate_name, prefix.value()))
+        },
+        Token![,],
+    )?;
+    Ok(mounts.into_iter().collect())
+}
+
+/// 源码中的一次 `generate_configure!(..)` 调用
+#[derive(Clone, Debug)]
+pub struct Invocation {
+    pub file: PathBuf,       // 调用所在的文件
+    pub line: usize,         // 调用所在的行号
+    pub args: ConfigureArgs, // 调用的参数
+}
+
+/// 查找 crate 的 src 目录中所有的 `generate_configure!(..)` 调用，按文件和行号排序
+///
+/// 无法解析的文件被跳过（编译时会报告），参数无效的调用返回错误。
+pub fn find_invocations(manifest_dir: &Path) -> Result<Vec<Invocation>, String> {
+    // src/bin 下的二进制目标也可能调用宏，不跳过任何目录
+    let files = collect_rs_files(&manifest_dir.join("src"), &[]);
+
+    let mut invocations = Vec::new();
+    for file in files {
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        let Ok(syntax) = syn::parse_file(&content) else {
+            continue;
+        };
+        let mut visitor = InvocationVisitor {
+            file: &file,
+            invocations: &mut invocations,
+            error: None,
+        };
+        visitor.visit_file(&syntax);
+        if let Some(e) = visitor.error {
+            return Err(e);
+        }
+    }
+    Ok(invocations)
+}
+
+/// 收集文件中的 generate_configure! 调用（包括内联模块和函数体中的调用）
+struct InvocationVisitor<'a> {
+    file: &'a Path,
+    invocations: &'a mut Vec<Invocation>,
+    error: Option<String>,
+}
+
+impl<'ast> Visit<'ast> for InvocationVisitor<'_> {
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        if mac
+            .path
+            .segments
+            .last()
+            .is_none_or(|segment| segment.ident != "generate_configure")
+        {
+            return;
+        }
+        let line = mac.path.segments[0].ident.span().start().line;
+        match mac.parse_body::<ConfigureArgs>() {
+            Ok(args) => self.invocations.push(Invocation {
+                file: self.file.to_path_buf(),
+                line,
+                args,
+            }),
+            Err(e) => {
+                self.error.get_or_insert_with(|| {
+                    format!(
+                        "{}:{}: invalid generate_configure! arguments: {}",
+                        self.file.display(),
+                        line,
+                        e
+                    )
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    fn parse(args: &str) -> syn::Result<ConfigureArgs> {
+        syn::parse_str(args)
+    }
+
+    fn error(args: &str) -> String {
+        parse(args).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn parses_globs_and_bare_strict() {
+        let args = parse(r#""src/**/*.rs", "!src/bin/**", strict"#).unwrap();
+        assert_eq!(args.patterns, ["src/**/*.rs", "!src/bin/**"]);
+        assert!(args.strict);
+        assert_eq!(args.name(), "configure");
+        assert!(parse("").unwrap().patterns.is_empty());
+    }
+
+    #[test]
+    fn parses_options() {
+        let args = parse(
+            r#"
+            name = "configure_public",
+            include = ["src/api/**"],
+            exclude = "!src/api/internal/**",
+            scope = "nested",
+            prefix = "/api",
+            strict = false,
+            strip = ["handler", "api::v1"],
+            collapse_duplicates = true,
+            kebab_case = false,
+            mount = { api_tool = "/tools", "admin-panel" = "" },
+            cache = false,
+            registration = "static",
+            external_data = ["AppConfig"],
+            "#,
+        )
+        .unwrap();
+        assert_eq!(args.name(), "configure_public");
+        assert_eq!(args.patterns, ["src/api/**", "!src/api/internal/**"]);
+        assert_eq!(args.scope, Some(ScopeStrategy::Nested));
+        assert_eq!(args.prefix.as_deref(), Some("/api"));
+        assert!(!args.strict);
+        assert_eq!(
+            args.strip,
+            Some(vec!["handler".to_string(), "api::v1".to_string()])
+        );
+        assert_eq!(args.collapse_duplicates, Some(true));
+        assert_eq!(args.kebab_case, Some(false));
+        assert_eq!(
+            args.mount,
+            [
+                ("api_tool".to_string(), "/tools".to_string()),
+                ("admin-panel".to_string(), String::new())
+            ]
+        );
+        assert_eq!(args.cache, Some(false));
+        assert_eq!(args.registration, Some(Registration::Static));
+        assert_eq!(args.external_data, ["AppConfig"]);
+    }
+
+    #[test]
+    fn name_accepts_identifier() {
+        assert_eq!(
+            parse("name = configure_admin").unwrap().name(),
+            "configure_admin"
+        );
+        assert!(parse(r#"name = "not an ident""#).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_options() {
+        assert!(error("strict, strict = true").contains("duplicate option `strict`"));
+        assert!(error(r#"mode = "flat""#).contains("unknown option"));
+        assert!(error(r#"scope = "tree""#).contains("unknown scope strategy `tree`"));
+        assert!(error(r#"registration = "dynamic""#).contains("unknown registration"));
+        assert!(error(r#"prefix = "api""#).contains("prefix must start with `/`"));
+        assert!(error(r#"mount = { api_tool = "tools" }"#).contains("mount prefix must start"));
+        assert!(error("cache = 1").contains("expected boolean literal"));
+    }
+
+    #[test]
+    fn finds_invocations_in_order() {
+        let dir = TestDir::new("configure_invocations");
+        dir.write(
+            "src/main.rs",
+            "fn main() {}\nroute_codegen::generate_configure!(name = configure_admin);\ngenerate_configure!();\n",
+        );
+        dir.write(
+            "src/bin/tool.rs",
+            "generate_configure!(\"src/bin/**\", strict);\nother_macro!(x = 1);\n",
+        );
+
+        let invocations = find_invocations(dir.path()).unwrap();
+        let found: Vec<(String, usize, String)> = invocations
+            .iter()
+            .map(|invocation| {
+                let file = invocation.file.strip_prefix(dir.path()).unwrap();
+                (
+                    crate::normalize_path(&file).into_owned(),
+                    invocation.line,
+                    invocation.args.name().to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("src/bin/tool.rs".to_string(), 1, "configure".to_string()),
+                ("src/main.rs".to_string(), 2, "configure_admin".to_string()),
+                ("src/main.rs".to_string(), 3, "configure".to_string()),
+            ]
+        );
+        assert!(invocations[0].args.strict);
+    }
+
+    #[test]
+    fn reports_invalid_invocation() {
+        let dir = TestDir::new("configure_invalid");
+        dir.write("src/lib.rs", "\ngenerate_configure!(scope = \"tree\");\n");
+        let error = find_invocations(dir.path()).unwrap_err();
+        assert!(
+            error.contains("lib.rs:2: invalid generate_configure! arguments"),
+            "{}",
+            error
+        );
+    }
+}
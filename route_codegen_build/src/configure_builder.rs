@@ -0,0 +1,690 @@
+use crate::tools::{is_rust_keyword, stable_hash};
+// 导入 is_rust_keyword 函数
+use crate::codegen_attr::{cfg_attribute, ScopeAnchor};
+use crate::diagnostics::Diagnostics;
+use crate::middleware::{wrap_tokens, RouteMiddleware};
+use crate::module_fn::{module_chain, ModuleFn, ModuleFns};
+use crate::prefix_rules::PrefixRules;
+use crate::provider::{provider_idents, provider_lets, RouteProvider};
+use crate::route_attr::RouteAttribute;
+use crate::route_order::sort_module_groups;
+use crate::scope_tree::{build_nested_scopes, sort_nested_groups};
+use crate::RouteFunction;
+use quote::quote;
+use syn::{punctuated::Punctuated, Ident, PathSegment, Token};
+
+/// 生成路由代码的选项
+#[derive(Clone, Debug)]
+pub struct CodegenOptions {
+    pub name: String,         // 生成的入口函数名（默认 configure）
+    pub scope: ScopeStrategy, // scope 的生成策略
+    pub prefix: String,       // 所有路由的公共前缀（如 /api）
+    pub rules: PrefixRules,   // 模块路径到 URL 前缀的映射规则
+}
+
+impl CodegenOptions {
+    /// 默认名称以外的路由集合生成在同名模块中，避免同一 crate 中多个集合的辅助函数和 ROUTES 冲突
+    pub fn in_module(&self) -> bool {
+        self.name != "configure"
+    }
+}
+
+impl Default for CodegenOptions {
+    fn default() -> Self {
+        Self {
+            name: "configure".to_string(),
+            scope: ScopeStrategy::Module,
+            prefix: String::new(),
+            rules: PrefixRules::default(),
+        }
+    }
+}
+
+/// scope 的生成策略
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScopeStrategy {
+    /// 不生成 scope，handler 直接使用自身的路径注册（有公共前缀时统一挂在一个 scope 下）
+    Flat,
+    /// 每个模块生成一个 `web::scope("/a/b/c")`，直接注册到根配置上
+    #[default]
+    Module,
+    /// 按模块层级生成嵌套的 scope
+    Nested,
+}
+
+impl std::str::FromStr for ScopeStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flat" => Ok(ScopeStrategy::Flat),
+            "module" => Ok(ScopeStrategy::Module),
+            "nested" => Ok(ScopeStrategy::Nested),
+            _ => Err(format!(
+                "unknown scope strategy `{}`; expected \"flat\", \"module\" or \"nested\"",
+                s
+            )),
+        }
+    }
+}
+
+/// 一个模块的路由分组
+#[derive(Clone)]
+pub struct ModuleGroup {
+    pub module_path: Vec<String>,          // 模块路径段（不含 crate）
+    pub scope: String,                     // 分组内路由的完整前缀（flat 模式下为公共前缀）
+    pub functions: Vec<RouteFunction>,     // 分组内的路由函数
+    pub middlewares: Vec<RouteMiddleware>, // 作用于分组的中间件（上级模块的在前）
+    pub providers: Vec<RouteProvider>,     // 注册在分组 scope 上的 app data provider
+    pub hooks: Vec<ModuleFn>,              // 分组内的 configure hook，在 handler 之后调用
+}
+
+/// 分组前，单个模块中的路由函数和 configure hook
+struct ModuleEntry {
+    crate_root: String,                // 所属 crate 的路径根
+    scope_anchor: Option<ScopeAnchor>, // 模块声明的 scope
+    functions: Vec<RouteFunction>,
+    hooks: Vec<ModuleFn>,
+}
+
+impl ModuleEntry {
+    fn new(crate_root: &str, scope_anchor: &Option<ScopeAnchor>) -> Self {
+        Self {
+            crate_root: crate_root.to_string(),
+            scope_anchor: scope_anchor.clone(),
+            functions: Vec::new(),
+            hooks: Vec::new(),
+        }
+    }
+}
+
+/// 按模块路径分组，计算每个分组的前缀，并按注册顺序策略排序
+///
+/// 映射规则可能把多个模块映射到同一个前缀（如 agency 和 agency::agency_api），
+/// actix 匹配到 scope 后不会回退到同名的 scope，因此前缀相同的模块合并为一个分组。
+///
+/// 合并后的中间件和 provider 作用于分组内的全部路由，各模块不一致时给出警告。
+pub fn group_functions_by_module(
+    functions: &[RouteFunction],
+    module_fns: &ModuleFns,
+    options: &CodegenOptions,
+    diagnostics: &Diagnostics,
+) -> Vec<ModuleGroup> {
+    // 只有 configure hook 的模块也需要一个分组来调用 hook
+    let mut grouped: std::collections::BTreeMap<Vec<String>, ModuleEntry> =
+        std::collections::BTreeMap::new();
+    for func in functions {
+        let entry = grouped
+            .entry(module_segments(&func.module_prefix))
+            .or_insert_with(|| ModuleEntry::new(&func.crate_root, &func.scope_anchor));
+        entry.functions.push(func.clone());
+    }
+    for hook in &module_fns.hooks {
+        let entry = grouped
+            .entry(hook.module_path())
+            .or_insert_with(|| ModuleEntry::new(&hook.crate_root, &hook.scope_anchor));
+        entry.hooks.push(hook.clone());
+    }
+
+    let mut by_scope: std::collections::BTreeMap<String, ModuleGroup> =
+        std::collections::BTreeMap::new();
+    // scope -> 合并到该分组的模块（用于诊断信息）
+    let mut merged_from: std::collections::BTreeMap<String, Vec<String>> =
+        std::collections::BTreeMap::new();
+    for (module_path, entry) in grouped {
+        let ModuleEntry {
+            crate_root,
+            scope_anchor,
+            functions,
+            hooks,
+        } = entry;
+        let scope = group_scope(&module_path, &crate_root, scope_anchor.as_ref(), options);
+        let middlewares = module_chain(&crate_root, &module_path, &module_fns.middlewares, |m| {
+            &m.function
+        });
+        let providers = module_chain(&crate_root, &module_path, &module_fns.providers, |p| {
+            &p.function
+        });
+        let module = module_display(&crate_root, &module_path);
+        match by_scope.get_mut(&scope) {
+            Some(group) => {
+                // flat 模式下中间件被忽略，provider 本来就注册在 App 上
+                if options.scope != ScopeStrategy::Flat {
+                    let merged = MergedModules {
+                        scope: &scope,
+                        existing: &merged_from[&scope],
+                        module: &module,
+                        diagnostics,
+                    };
+                    merged.warn(
+                        "#[route_middleware]",
+                        &group.middlewares,
+                        &middlewares,
+                        |m| &m.function,
+                    );
+                    merged.warn("#[route_provider]", &group.providers, &providers, |p| {
+                        &p.function
+                    });
+                }
+                if let Some(modules) = merged_from.get_mut(&scope) {
+                    modules.push(module);
+                }
+                group.functions.extend(functions);
+                group.hooks.extend(hooks);
+                merge_unique(&mut group.middlewares, middlewares);
+                merge_unique(&mut group.providers, providers);
+            }
+            None => {
+                merged_from.insert(scope.clone(), vec![module]);
+                by_scope.insert(
+                    scope.clone(),
+                    ModuleGroup {
+                        module_path,
+                        scope,
+                        functions,
+                        middlewares,
+                        providers,
+                        hooks,
+                    },
+                );
+            }
+        }
+    }
+
+    let mut grouped: Vec<ModuleGroup> = by_scope.into_values().collect();
+    sort_module_groups(&mut grouped);
+    if options.scope == ScopeStrategy::Nested {
+        sort_nested_groups(&mut grouped);
+    }
+    grouped
+}
+
+/// 模块的完整路径，用于诊断信息（如 crate::handler::agency）
+fn module_display(crate_root: &str, module_path: &[String]) -> String {
+    std::iter::once(crate_root)
+        .chain(module_path.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// 合并到同一个 scope 的模块，用于报告中间件和 provider 作用范围的变化
+struct MergedModules<'a> {
+    scope: &'a str,         // 合并后的 scope
+    existing: &'a [String], // 已经合并到分组中的模块
+    module: &'a str,        // 正在合并的模块
+    diagnostics: &'a Diagnostics,
+}
+
+impl MergedModules<'_> {
+    /// 分组已有的和正在合并的模块各自的中间件 / provider 不同时，
+    /// 只属于一方的条目会作用到另一方的路由上
+    fn warn<T: PartialEq>(
+        &self,
+        kind: &str,
+        existing: &[T],
+        items: &[T],
+        function: impl Fn(&T) -> &ModuleFn,
+    ) {
+        let names = |items: &[T], other: &[T]| {
+            items
+                .iter()
+                .filter(|item| !other.contains(item))
+                .map(|item| {
+                    let function = function(item);
+                    format!("`{}` ({})", function.name, function.location())
+                })
+                .collect::<Vec<_>>()
+        };
+        let verb = |count: usize| if count == 1 { "applies" } else { "apply" };
+        let scope = if self.scope.is_empty() {
+            "/"
+        } else {
+            self.scope
+        };
+        let existing_modules = self
+            .existing
+            .iter()
+            .map(|module| format!("`{}`", module))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let to_module = names(existing, items);
+        if !to_module.is_empty() {
+            self.diagnostics.warn(format!(
+                "modules {} and `{}` are merged into scope `{}`; {} {} now also {} to routes in `{}`. Give the modules different scopes (#[route_scope]) to keep them separate",
+                existing_modules,
+                self.module,
+                scope,
+                kind,
+                to_module.join(", "),
+                verb(to_module.len()),
+                self.module
+            ));
+        }
+        let to_existing = names(items, existing);
+        if !to_existing.is_empty() {
+            self.diagnostics.warn(format!(
+                "modules {} and `{}` are merged into scope `{}`; {} {} now also {} to routes in {}. Give the modules different scopes (#[route_scope]) to keep them separate",
+                existing_modules,
+                self.module,
+                scope,
+                kind,
+                to_existing.join(", "),
+                verb(to_existing.len()),
+                existing_modules
+            ));
+        }
+    }
+}
+
+/// 把 items 中尚未出现的元素追加到 target
+fn merge_unique<T: PartialEq>(target: &mut Vec<T>, items: Vec<T>) {
+    for item in items {
+        if !target.contains(&item) {
+            target.push(item);
+        }
+    }
+}
+
+/// 分组内路由的完整前缀，根前缀为空字符串
+fn group_scope(
+    module_path: &[String],
+    crate_root: &str,
+    scope_anchor: Option<&ScopeAnchor>,
+    options: &CodegenOptions,
+) -> String {
+    let prefix = options.prefix.trim_end_matches('/');
+    match options.scope {
+        ScopeStrategy::Flat => prefix.to_string(),
+        ScopeStrategy::Module | ScopeStrategy::Nested => {
+            // 声明了 #[route_scope] 的模块从声明的前缀开始，追加相对的子模块路径
+            if let Some(anchor) = scope_anchor {
+                let relative = module_path.get(anchor.depth..).unwrap_or_default();
+                return format!(
+                    "{}{}",
+                    prefix,
+                    options.rules.relative_scope(&anchor.scope, relative)
+                );
+            }
+
+            // 依赖 crate 的模块路径以 crate 名称开头
+            let crate_name = Some(crate_root).filter(|root| *root != "crate");
+            let module_path = match crate_name {
+                Some(_) => module_path.get(1..).unwrap_or_default(),
+                None => module_path,
+            };
+            format!("{}{}", prefix, options.rules.scope(crate_name, module_path))
+        }
+    }
+}
+
+/// 生成 configure_xxx 和 register_xxx 函数、入口函数中的注册语句及路由信息
+///
+/// nested 模式下不生成 configure_xxx，register_xxx 在嵌套的 scope 树中调用。
+pub fn generate_configure_functions_and_routes(
+    grouped: &[ModuleGroup],
+    module_fns: &ModuleFns,
+    options: &CodegenOptions,
+) -> (
+    Vec<proc_macro2::TokenStream>,
+    Vec<proc_macro2::TokenStream>,
+    Vec<RouteLog>,
+) {
+    let providers = &module_fns.providers;
+    let mut all_configure_fns = Vec::new();
+    // 先调用所有 provider；flat 模式下没有模块 scope，provider 统一注册在入口函数上
+    let mut all_configure_calls = vec![provider_lets(providers, options)];
+    if options.scope == ScopeStrategy::Flat {
+        let idents = provider_idents(providers, providers);
+        all_configure_calls.push(quote! {
+            #(cfg.app_data(#idents.clone());)*
+        });
+    }
+    let mut all_register_idents = Vec::new();
+    let mut all_routes = Vec::new();
+
+    for group in grouped {
+        let (configure_fn, register_fn, calls, routes) =
+            generate_module_configure(group, providers, options);
+        all_configure_fns.push(register_fn);
+        all_configure_fns.push(configure_fn);
+        all_configure_calls.extend(calls);
+        all_register_idents.push(register_ident(group));
+        all_routes.extend(routes);
+    }
+
+    if options.scope == ScopeStrategy::Nested {
+        all_configure_calls.push(build_nested_scopes(
+            grouped,
+            &all_register_idents,
+            providers,
+            options,
+        ));
+    }
+
+    (all_configure_fns, all_configure_calls, all_routes)
+}
+
+/// 最终注册的一条路由：方法 + 完整路径
+pub struct RouteEndpoint<'a> {
+    pub method: &'a str,
+    pub path: String, // 完整路径（scope + 路由路径）
+    pub route: &'a RouteAttribute,
+    pub function: &'a RouteFunction,
+}
+
+/// 按注册顺序展开所有模块中的路由
+pub fn collect_endpoints(groups: &[ModuleGroup]) -> Vec<RouteEndpoint<'_>> {
+    let mut endpoints = Vec::new();
+    for group in groups {
+        for function in &group.functions {
+            for route in &function.routes {
+                for method in &route.methods {
+                    endpoints.push(RouteEndpoint {
+                        method: method.as_str(),
+                        path: format!("{}{}", group.scope, route.path),
+                        route,
+                        function,
+                    });
+                }
+            }
+        }
+    }
+    endpoints
+}
+
+/// 模块路径字符串（如 handler::agency）拆分为段
+pub fn module_segments(module_prefix: &str) -> Vec<String> {
+    module_prefix
+        .split("::")
+        .map(|s| s.to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// 从生成代码的位置引用 handler 等函数的路径（如 super::handler::nation::hello）
+pub fn item_path(
+    crate_root: &str,
+    module_prefix: &str,
+    name: &str,
+    options: &CodegenOptions,
+) -> Punctuated<PathSegment, Token![::]> {
+    let mut segments = Punctuated::<PathSegment, Token![::]>::new();
+    // 生成在路由集合模块中时，本地 handler 的路径相对于上一级（调用宏的位置）
+    if crate_root == "crate" && options.in_module() {
+        segments.push(PathSegment::from(Ident::new(
+            "super",
+            proc_macro2::Span::call_site(),
+        )));
+    }
+    for s in module_prefix
+        .split("::")
+        .filter(|s| !s.is_empty())
+        .chain([name])
+    {
+        let ident_segment = if is_rust_keyword(s) {
+            Ident::new(&format!("r#{}", s), proc_macro2::Span::call_site())
+        } else {
+            Ident::new(s, proc_macro2::Span::call_site())
+        };
+        segments.push(PathSegment::from(ident_segment));
+    }
+    segments
+}
+
+/// 分组的 register 函数名
+fn register_ident(group: &ModuleGroup) -> Ident {
+    Ident::new(
+        &format!("register_{}", module_ident_suffix(&group.module_path)),
+        proc_macro2::Span::call_site(),
+    )
+}
+
+/// 由模块路径生成的函数名后缀，不同的模块路径得到不同的后缀
+///
+/// 路径段之间用 `__` 连接（`a_b::c` -> `a_b__c`，`a::b_c` -> `a__b_c`），根模块为 `root`；
+/// 路径段本身包含 `__` 或以 `_` 开头、结尾，或者模块路径恰好是 `root` 时，连接结果可能有歧义，
+/// 此时追加 `___` 和完整路径的稳定哈希（普通路径连接后不会出现 `___`）。
+fn module_ident_suffix(module_path: &[String]) -> String {
+    if module_path.is_empty() {
+        return "root".to_string();
+    }
+    let joined = module_path.join("__");
+    let ambiguous = joined == "root"
+        || module_path.iter().any(|segment| {
+            segment.contains("__") || segment.starts_with('_') || segment.ends_with('_')
+        });
+    if ambiguous {
+        let hash = stable_hash(module_path.join("::").as_bytes());
+        format!("{}___{:016x}", joined, hash)
+    } else {
+        joined
+    }
+}
+
+/// 为每个模块生成 configure/register 函数及相关内容
+///
+/// 返回的注册语句在入口函数中执行，provider 变量由入口函数传入 configure_xxx。
+fn generate_module_configure(
+    group: &ModuleGroup,
+    providers: &[RouteProvider],
+    options: &CodegenOptions,
+) -> (
+    proc_macro2::TokenStream,
+    proc_macro2::TokenStream,
+    Vec<proc_macro2::TokenStream>,
+    Vec<RouteLog>,
+) {
+    let ModuleGroup {
+        module_path,
+        scope: mod_scope,
+        functions,
+        middlewares,
+        providers: group_providers,
+        hooks,
+    } = group;
+    let configure_ident = Ident::new(
+        &format!("configure_{}", module_ident_suffix(module_path)),
+        proc_macro2::Span::call_site(),
+    );
+
+    let services = functions.iter().map(|f| {
+        let segments = item_path(&f.crate_root, &f.module_prefix, &f.name, options);
+        let cfg_attr = cfg_attribute(&f.cfgs);
+        quote! {
+            #cfg_attr
+            cfg.service(#segments);
+        }
+    });
+    let hooks = hooks.iter().map(|hook| {
+        let path = hook.path(options);
+        let cfg_attr = cfg_attribute(&hook.cfgs);
+        quote! {
+            #cfg_attr
+            #path(cfg);
+        }
+    });
+    let register_ident = register_ident(group);
+
+    let register_fn = quote! {
+
+        pub fn #register_ident(cfg: &mut actix_web::web::ServiceConfig) {
+            #(#services)*
+            #(#hooks)*
+        }
+
+    };
+
+    // flat 模式下不为模块生成 scope，公共前缀由入口函数统一挂载；
+    // nested 模式下 scope 由入口函数中的 scope 树生成
+    let configure_fn = match options.scope {
+        ScopeStrategy::Flat => quote! {
+
+            pub fn #configure_ident(cfg: &mut actix_web::web::ServiceConfig) {
+                #register_ident(cfg);
+            }
+
+        },
+        ScopeStrategy::Module if !group_providers.is_empty() => {
+            // provider 变量的类型由入口函数推导，这里使用泛型参数接收
+            let wraps = wrap_tokens(middlewares, options);
+            let params: Vec<Ident> = (0..group_providers.len())
+                .map(|index| quote::format_ident!("provider_{}", index))
+                .collect();
+            let types: Vec<Ident> = (0..group_providers.len())
+                .map(|index| quote::format_ident!("P{}", index))
+                .collect();
+            let args = provider_idents(group_providers, providers);
+            let configure_fn = quote! {
+
+                pub fn #configure_ident<#(#types: Clone + 'static),*>(
+                    cfg: &mut actix_web::web::ServiceConfig,
+                    #(#params: &#types),*
+                ) {
+                    cfg.service(
+                        actix_web::web::scope(#mod_scope)
+                            #(.app_data(#params.clone()))*
+                            #wraps
+                            .configure(#register_ident),
+                    );
+                }
+
+            };
+            let call = quote! {
+                #configure_ident(cfg, #(&#args),*);
+            };
+            let routes = module_routes(functions, mod_scope);
+            return (configure_fn, register_fn, vec![call], routes);
+        }
+        ScopeStrategy::Module => {
+            let wraps = wrap_tokens(middlewares, options);
+            quote! {
+
+                pub fn #configure_ident(cfg: &mut actix_web::web::ServiceConfig) {
+                    cfg.service(actix_web::web::scope(#mod_scope)#wraps.configure(#register_ident));
+                }
+
+            }
+        }
+        ScopeStrategy::Nested => {
+            let routes = module_routes(functions, mod_scope);
+            return (quote! {}, register_fn, Vec::new(), routes);
+        }
+    };
+
+    let call = quote! {
+        cfg.configure(#configure_ident);
+    };
+    let routes = module_routes(functions, mod_scope);
+    (configure_fn, register_fn, vec![call], routes)
+}
+
+/// 启动时打印的一条注册日志
+pub struct RouteLog {
+    method: String,    // HTTP 方法
+    path: String,      // 完整路径（scope + 路由路径）
+    cfgs: Vec<String>, // 与注册代码相同的 #[cfg] 条件
+}
+
+/// 分组内路由的注册日志
+fn module_routes(functions: &[RouteFunction], scope: &str) -> Vec<RouteLog> {
+    functions
+        .iter()
+        .flat_map(|f| {
+            f.routes.iter().flat_map(move |route| {
+                route.methods.iter().map(move |method| RouteLog {
+                    method: method.clone(),
+                    path: format!("{}{}", scope, route.path),
+                    cfgs: f.route_cfgs(route),
+                })
+            })
+        })
+        .collect()
+}
+
+/// 构建最终的 configure 函数（函数名由 options.name 指定）
+pub fn build_configure_function(
+    all_configure_fns: Vec<proc_macro2::TokenStream>,
+    all_configure_calls: Vec<proc_macro2::TokenStream>,
+    all_routes: Vec<RouteLog>,
+    options: &CodegenOptions,
+) -> proc_macro2::TokenStream {
+    let configure_ident = Ident::new(&options.name, proc_macro2::Span::call_site());
+
+    // 生成日志，带有与注册代码相同的 #[cfg] 条件
+    let route_logs = all_routes.iter().map(|RouteLog { method, path, cfgs }| {
+        let cfg_attr = cfg_attribute(cfgs);
+        quote! {
+            #cfg_attr
+            log::info!("🚀 Registered route: {} {}", #method, #path);
+        }
+    });
+
+    // flat 模式下的公共前缀：所有 handler 挂在同一个 scope 下（actix 匹配到 scope 后不会回退到同名 scope）
+    let prefix = options.prefix.trim_end_matches('/');
+    let configure_calls = if options.scope == ScopeStrategy::Flat && !prefix.is_empty() {
+        quote! {
+            cfg.service(actix_web::web::scope(#prefix).configure(|cfg| {
+                #(#all_configure_calls)*
+            }));
+        }
+    } else {
+        quote! {
+            #(#all_configure_calls)*
+        }
+    };
+
+    let configure_all = quote! {
+        #(#all_configure_fns)*
+
+        pub fn #configure_ident(cfg: &mut actix_web::web::ServiceConfig) {
+            {
+                use std::sync::atomic::{AtomicBool, Ordering};
+                static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+                if INITIALIZED.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+                    #(#route_logs)*
+                }
+            }
+
+            #configure_calls
+        }
+    };
+    // 调用格式化函数打印输出
+    // format_token_stream(&configure_all);
+    // 调用格式化函数
+    configure_all
+}
+
+/*fn format_token_stream(stream: &proc_macro2::TokenStream) {
+    let code = stream.to_string();
+
+    // 使用 syn 解析代码
+    let syntax_tree = syn::parse_file(&code).expect("Failed to parse code");
+    // 使用 prettyplease 格式化
+    let formatted_code = prettyplease::unparse(&syntax_tree);
+    println!("formatting code \n{}", formatted_code);
+}*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suffix(module_path: &[&str]) -> String {
+        let module_path: Vec<String> = module_path.iter().map(|s| s.to_string()).collect();
+        module_ident_suffix(&module_path)
+    }
+
+    #[test]
+    fn module_suffixes_are_distinct() {
+        assert_eq!(suffix(&[]), "root");
+        assert_eq!(suffix(&["api", "user"]), "api__user");
+        assert_ne!(suffix(&["a_b", "c"]), suffix(&["a", "b_c"]));
+        assert_ne!(suffix(&["a__b"]), suffix(&["a", "b"]));
+        assert_ne!(suffix(&["a_", "b"]), suffix(&["a", "_b"]));
+
+        let root = suffix(&["root"]);
+        assert_ne!(root, "root");
+        assert!(root.starts_with("root___"));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::codegen_attr::is_codegen_attribute;
+use crate::module_fn::ModuleFn;
+use syn::visit::{self, Visit};
+use syn::{Expr, ExprCall, ExprMethodCall, ItemFn, Lit, ReturnType};
+
+/// 读取函数上的 #[configure_hook]，未标注时返回 None，签名不是 `fn(&mut ServiceConfig)` 时返回错误
+///
+/// hook 由所在模块生成的 register 函数在注册完 handler 后调用，
+/// 用于注册 `web::resource`、`default_service`、静态文件等无法用路由属性描述的服务。
+/// hook 中以字面量注册的路径（`web::resource("/res")`、`cfg.route("/res", ..)`）会被记录下来，
+/// 用于检查它们是否被同一 scope 中先注册的 handler（如 `/{id}`）遮蔽。
+pub fn parse_configure_hook(fn_item: &ItemFn) -> Option<Result<ModuleFn, String>> {
+    if !fn_item
+        .attrs
+        .iter()
+        .any(|attr| is_codegen_attribute(attr, "configure_hook"))
+    {
+        return None;
+    }
+
+    let sig = &fn_item.sig;
+    if sig.asyncness.is_some()
+        || sig.inputs.len() != 1
+        || !matches!(sig.output, ReturnType::Default)
+    {
+        return Some(Err(format!(
+            "#[configure_hook] `{}` must have the signature `fn(&mut web::ServiceConfig)`",
+            sig.ident
+        )));
+    }
+    let mut paths = HookPaths::default();
+    paths.visit_block(&fn_item.block);
+    Some(Ok(ModuleFn {
+        paths: paths.0,
+        ..ModuleFn::new(fn_item)
+    }))
+}
+
+/// 收集 `resource("..")` 和 `.route("..", ..)` 中的字符串字面量路径
+#[derive(Default)]
+struct HookPaths(Vec<String>);
+
+impl HookPaths {
+    fn push_literal(&mut self, expr: Option<&Expr>) {
+        if let Some(Expr::Lit(lit)) = expr
+            && let Lit::Str(path) = &lit.lit
+        {
+            self.0.push(path.value());
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for HookPaths {
+    fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+        if let Expr::Path(func) = &*call.func
+            && func
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "resource")
+        {
+            self.push_literal(call.args.first());
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        if call.method == "route" && call.args.len() == 2 {
+            self.push_literal(call.args.first());
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_literal_hook_paths() {
+        let fn_item: ItemFn = syn::parse_quote! {
+            #[route_codegen::configure_hook]
+            fn hook(cfg: &mut web::ServiceConfig) {
+                cfg.service(web::resource("/res").route(web::get().to(get_res)));
+                cfg.route("/other", web::get().to(other));
+                cfg.service(web::resource(PATH));
+            }
+        };
+        let hook = parse_configure_hook(&fn_item).unwrap().unwrap();
+        assert_eq!(hook.paths, ["/res", "/other"]);
+    }
+}
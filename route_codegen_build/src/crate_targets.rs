@@ -0,0 +1,143 @@
+use crate::dependency_tracker::DependencyTracker;
+use crate::diagnostics::Diagnostics;
+use crate::module_resolver::ModuleResolver;
+use crate::read_cargo_toml;
+use crate::scan_cache::ScanCache;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// crate 各个编译目标的根文件，按 Cargo 的规则确定
+#[derive(Debug, Default)]
+pub struct CrateTargets {
+    pub lib: Option<PathBuf>,     // 库目标：`[lib] path`，默认 src/lib.rs
+    pub lib_name: Option<String>, // `[lib] name`
+    pub proc_macro: bool,         // `[lib] proc-macro = true`
+    pub main: Option<PathBuf>,    // 默认的可执行目标 src/main.rs
+    pub bins: Vec<PathBuf>,       // 其他可执行目标：`[[bin]] path`、src/bin/*.rs、src/bin/*/main.rs
+}
+
+impl CrateTargets {
+    /// 读取 manifest_dir 中 Cargo.toml 声明的目标，并补充 Cargo 自动发现的目标
+    pub fn read(manifest_dir: &Path) -> Result<CrateTargets, String> {
+        let manifest_path = manifest_dir.join("Cargo.toml");
+        let cargo_toml = read_cargo_toml(&manifest_path)?;
+        let src_dir = manifest_dir.join("src");
+        let mut targets = CrateTargets::default();
+
+        let lib_path = match cargo_toml.get("lib").and_then(|lib| lib.get("path")) {
+            Some(path) => Some(manifest_dir.join(path.as_str().ok_or_else(|| {
+                format!("{}: `lib.path` must be a string", manifest_path.display())
+            })?)),
+            None => Some(src_dir.join("lib.rs")),
+        };
+        targets.lib = lib_path.filter(|path| path.is_file());
+        targets.lib_name = cargo_toml
+            .get("lib")
+            .and_then(|lib| lib.get("name"))
+            .and_then(|name| name.as_str())
+            .map(|name| name.to_string());
+        targets.proc_macro = cargo_toml
+            .get("lib")
+            .and_then(|lib| lib.get("proc-macro"))
+            .and_then(|proc_macro| proc_macro.as_bool())
+            .unwrap_or(false);
+        targets.main = Some(src_dir.join("main.rs")).filter(|path| path.is_file());
+
+        if let Some(bins) = cargo_toml.get("bin").and_then(|bins| bins.as_array()) {
+            for bin in bins {
+                match (bin.get("path"), bin.get("name")) {
+                    (Some(path), _) => {
+                        let path = path.as_str().ok_or_else(|| {
+                            format!("{}: `bin.path` must be a string", manifest_path.display())
+                        })?;
+                        targets.push_bin(manifest_dir.join(path));
+                    }
+                    (None, Some(name)) => {
+                        let name = name.as_str().unwrap_or_default();
+                        targets.push_bin(src_dir.join("bin").join(format!("{}.rs", name)));
+                        targets.push_bin(src_dir.join("bin").join(name).join("main.rs"));
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+        targets.discover_bins(&src_dir.join("bin"));
+        Ok(targets)
+    }
+
+    /// 记录存在的可执行目标根文件，忽略重复项
+    fn push_bin(&mut self, path: PathBuf) {
+        if path.is_file() && self.main.as_ref() != Some(&path) && !self.bins.contains(&path) {
+            self.bins.push(path);
+        }
+    }
+
+    /// Cargo 自动发现的 src/bin/*.rs 和 src/bin/*/main.rs
+    fn discover_bins(&mut self, bin_dir: &Path) {
+        let Ok(entries) = fs::read_dir(bin_dir) else {
+            return;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .collect();
+        paths.sort();
+        for path in paths {
+            if path.is_dir() {
+                self.push_bin(path.join("main.rs"));
+            } else if path.extension().and_then(|e| e.to_str()) == Some("rs") {
+                self.push_bin(path);
+            }
+        }
+    }
+
+    /// 生成代码所在 crate 的根文件
+    ///
+    /// `invoked_from` 为调用 `generate_configure!` 的文件：它本身是某个目标的根文件时使用该目标，
+    /// 否则使用模块树中包含该文件的目标，依次检查库目标、src/main.rs 和其他可执行目标；
+    /// 未知调用位置（如 build.rs）或没有目标包含该文件时依次使用 src/main.rs、库目标和第一个可执行目标。
+    pub fn local_root(
+        &self,
+        invoked_from: Option<&Path>,
+        tracker: &DependencyTracker,
+        cache: &ScanCache,
+    ) -> Option<PathBuf> {
+        if let Some(file) = invoked_from.map(canonical) {
+            let mut roots = self.main.iter().chain(&self.bins).chain(&self.lib);
+            if let Some(root) = roots.find(|root| canonical(root) == file) {
+                return Some(root.clone());
+            }
+            let mut roots = self.lib.iter().chain(&self.main).chain(&self.bins);
+            if let Some(root) = roots.find(|root| module_tree_contains(root, &file, tracker, cache))
+            {
+                return Some(root.clone());
+            }
+        }
+        self.main
+            .as_ref()
+            .or(self.lib.as_ref())
+            .or(self.bins.first())
+            .cloned()
+    }
+}
+
+/// 从 root 出发的模块树是否包含 file（file 已规范化）
+///
+/// 解析过的文件登记为编译依赖：模块声明变化后重新确定生成代码所在的目标。
+fn module_tree_contains(
+    root: &Path,
+    file: &Path,
+    tracker: &DependencyTracker,
+    cache: &ScanCache,
+) -> bool {
+    // 这里只关心模块树的结构，解析过程中的诊断信息在正式扫描时报告
+    let diagnostics = Diagnostics::new().quiet(true);
+    let resolver = ModuleResolver::new(tracker, cache, &diagnostics);
+    resolver.resolve_crate(root, "crate", &[]);
+    resolver.visited_files().contains(file)
+}
+
+/// 规范化路径用于比较，无法规范化时原样返回
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
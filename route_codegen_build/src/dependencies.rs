@@ -0,0 +1,341 @@
+use crate::diagnostics::Diagnostics;
+use crate::read_cargo_toml;
+use crate::workspace::{normalize, workspace_root};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use toml::Value;
+
+/// 被扫描 crate 的一个 path 依赖，其中的 handler 会一起注册
+#[derive(Debug, Clone)]
+pub struct PathDependency {
+    pub key: String,       // `[dependencies]` 中的键
+    pub package: String,   // 依赖的包名（`package = ".."` 重命名时与键不同）
+    pub renamed: bool,     // 是否通过 `package = ".."` 重命名
+    pub dir: PathBuf,      // 依赖的目录
+    pub cfgs: Vec<String>, // 启用该依赖所需的条件（optional 依赖的 feature、target 依赖的平台）
+}
+
+impl PathDependency {
+    /// 被扫描 crate 中引用该依赖使用的名称：重命名时为依赖的键，否则为依赖的库名
+    pub fn crate_name(&self, lib_name: Option<&str>) -> String {
+        if self.renamed {
+            self.key.replace('-', "_")
+        } else {
+            lib_name.unwrap_or(&self.package).replace('-', "_")
+        }
+    }
+}
+
+/// 读取 manifest_dir 中 Cargo.toml 的 `[dependencies]` 和 `[target.*.dependencies]` 中的 path 依赖
+///
+/// - `workspace = true` 的依赖从所在 workspace 根目录的 `[workspace.dependencies]` 中读取包名和路径，
+///   路径相对 workspace 根目录；找不到对应条目时报告问题；
+/// - optional 依赖只在启用它的 feature 打开时注册，生成的代码带 `#[cfg(feature = "..")]`；
+///   没有任何 feature 能启用的 optional 依赖不扫描；
+/// - `[target.'cfg(..)'.dependencies]` 中的依赖带有相同的 cfg 条件，以目标三元组为键的依赖不扫描。
+///
+/// registry 和 git 依赖没有本地源码，不扫描。
+pub fn read_path_dependencies(
+    manifest_dir: &Path,
+    diagnostics: &Diagnostics,
+) -> Result<Vec<PathDependency>, String> {
+    let manifest_path = manifest_dir.join("Cargo.toml");
+    let cargo_toml = read_cargo_toml(&manifest_path)?;
+    let workspace = read_workspace_dependencies(manifest_dir);
+    let features = read_features(cargo_toml.get("features"));
+
+    let mut tables: Vec<(&Value, Option<String>)> = cargo_toml
+        .get("dependencies")
+        .map(|deps| (deps, None))
+        .into_iter()
+        .collect();
+    if let Some(Value::Table(targets)) = cargo_toml.get("target") {
+        for (target, table) in targets {
+            let Some(deps) = table.get("dependencies") else {
+                continue;
+            };
+            if let Some(predicate) = target
+                .strip_prefix("cfg(")
+                .and_then(|rest| rest.strip_suffix(')'))
+            {
+                tables.push((deps, Some(predicate.to_string())));
+            }
+        }
+    }
+
+    let mut dependencies: Vec<PathDependency> = Vec::new();
+    for (table, target_cfg) in tables {
+        let Value::Table(deps) = table else {
+            continue;
+        };
+        for (key, spec) in deps {
+            let optional = spec.get("optional").and_then(|o| o.as_bool()) == Some(true);
+            // workspace = true 时包名和路径来自 [workspace.dependencies]，路径相对 workspace 根目录
+            let (spec, base_dir) = match spec.get("workspace").and_then(|w| w.as_bool()) {
+                Some(true) => match workspace
+                    .as_ref()
+                    .and_then(|(root, deps)| deps.get(key).map(|spec| (spec, root.as_path())))
+                {
+                    Some(inherited) => inherited,
+                    None => {
+                        diagnostics.problem(format!(
+                            "{}: dependency `{}` sets `workspace = true`, but no `[workspace.dependencies]` entry for it was found",
+                            manifest_path.display(),
+                            key
+                        ));
+                        continue;
+                    }
+                },
+                _ => (spec, manifest_dir),
+            };
+            let Some(path) = spec.get("path").and_then(|p| p.as_str()) else {
+                continue;
+            };
+
+            let mut cfgs: Vec<String> = target_cfg.iter().cloned().collect();
+            if optional {
+                match feature_cfg(key, &features) {
+                    Some(cfg) => cfgs.push(cfg),
+                    None => continue,
+                }
+            }
+
+            let package = spec.get("package").and_then(|p| p.as_str());
+            let dependency = PathDependency {
+                key: key.clone(),
+                package: package.unwrap_or(key).to_string(),
+                renamed: package.is_some(),
+                dir: base_dir.join(path),
+                cfgs,
+            };
+            // 同一个依赖出现在多个表中时，保留条件最少的一项
+            match dependencies
+                .iter_mut()
+                .find(|d| normalize(&d.dir) == normalize(&dependency.dir))
+            {
+                Some(existing) if existing.cfgs.len() > dependency.cfgs.len() => {
+                    *existing = dependency
+                }
+                Some(_) => {}
+                None => dependencies.push(dependency),
+            }
+        }
+    }
+    Ok(dependencies)
+}
+
+/// 所在 workspace 的根目录及其 `[workspace.dependencies]` 表
+fn read_workspace_dependencies(manifest_dir: &Path) -> Option<(PathBuf, Value)> {
+    let root = workspace_root(manifest_dir)?;
+    let cargo_toml = read_cargo_toml(&root.join("Cargo.toml")).ok()?;
+    let dependencies = cargo_toml.get("workspace")?.get("dependencies")?.clone();
+    Some((root, dependencies))
+}
+
+/// `[features]` 表：feature 名称 -> 启用的条目
+fn read_features(features: Option<&Value>) -> HashMap<String, Vec<String>> {
+    let Some(Value::Table(features)) = features else {
+        return HashMap::new();
+    };
+    features
+        .iter()
+        .map(|(name, values)| {
+            let values = values
+                .as_array()
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            (name.clone(), values)
+        })
+        .collect()
+}
+
+/// 启用 optional 依赖的 cfg 条件：依赖的隐式 feature（没有用 `dep:` 引用时存在），
+/// 以及直接或间接启用它的所有 feature；没有任何 feature 能启用时返回 None
+fn feature_cfg(key: &str, features: &HashMap<String, Vec<String>>) -> Option<String> {
+    let dep_syntax = format!("dep:{}", key);
+    let implicit = !features
+        .values()
+        .flatten()
+        .any(|value| *value == dep_syntax);
+
+    let mut enabling = BTreeSet::new();
+    if implicit {
+        enabling.insert(key.to_string());
+    }
+    // "dep:foo"、"foo"（隐式 feature）和 "foo/feature" 会启用依赖，"foo?/feature" 不会
+    let enables_dep = |value: &str| {
+        value == dep_syntax
+            || (implicit && value == key)
+            || value
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.starts_with('/'))
+    };
+    for (name, values) in features {
+        if values.iter().any(|value| enables_dep(value)) {
+            enabling.insert(name.clone());
+        }
+    }
+    // 启用上述 feature 的 feature 也会间接启用依赖
+    loop {
+        let before = enabling.len();
+        for (name, values) in features {
+            if values.iter().any(|value| enabling.contains(value)) {
+                enabling.insert(name.clone());
+            }
+        }
+        if enabling.len() == before {
+            break;
+        }
+    }
+
+    let predicates: Vec<String> = enabling
+        .iter()
+        .map(|feature| format!("feature = {:?}", feature))
+        .collect();
+    match predicates.as_slice() {
+        [] => None,
+        [predicate] => Some(predicate.clone()),
+        _ => Some(format!("any({})", predicates.join(", "))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    fn features(toml: &str) -> HashMap<String, Vec<String>> {
+        read_features(Some(&toml::from_str(toml).unwrap()))
+    }
+
+    #[test]
+    fn optional_dependency_uses_implicit_feature() {
+        assert_eq!(
+            feature_cfg("admin", &features("")).as_deref(),
+            Some(r#"feature = "admin""#)
+        );
+        assert_eq!(
+            feature_cfg("admin", &features(r#"full = ["admin", "other"]"#)).as_deref(),
+            Some(r#"any(feature = "admin", feature = "full")"#)
+        );
+    }
+
+    #[test]
+    fn dep_syntax_hides_implicit_feature() {
+        let features = features(
+            r#"
+            backoffice = ["dep:admin"]
+            all = ["backoffice"]
+            weak = ["admin?/extra"]
+            "#,
+        );
+        assert_eq!(
+            feature_cfg("admin", &features).as_deref(),
+            Some(r#"any(feature = "all", feature = "backoffice")"#)
+        );
+    }
+
+    #[test]
+    fn dependency_feature_enables_dependency() {
+        assert_eq!(
+            feature_cfg("admin", &features(r#"extra = ["admin/extra"]"#)).as_deref(),
+            Some(r#"any(feature = "admin", feature = "extra")"#)
+        );
+    }
+
+    #[test]
+    fn reads_conditional_path_dependencies() {
+        let dir = TestDir::new("path_dependencies");
+        dir.write(
+            "app/Cargo.toml",
+            r#"
+            [package]
+            name = "app"
+
+            [dependencies]
+            serde = "1"
+            api = { path = "../api" }
+            my_admin = { path = "../admin", package = "admin", optional = true }
+
+            [features]
+            backoffice = ["dep:my_admin"]
+
+            [target.'cfg(unix)'.dependencies]
+            unix_only = { path = "../unix_only" }
+            api = { path = "../api" }
+
+            [target.x86_64-pc-windows-msvc.dependencies]
+            windows_only = { path = "../windows_only" }
+            "#,
+        );
+
+        let diagnostics = Diagnostics::new();
+        let dependencies = read_path_dependencies(&dir.path().join("app"), &diagnostics).unwrap();
+        let summary: Vec<(&str, &str, bool, Vec<String>)> = dependencies
+            .iter()
+            .map(|d| {
+                (
+                    d.key.as_str(),
+                    d.package.as_str(),
+                    d.renamed,
+                    d.cfgs.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("api", "api", false, vec![]),
+                (
+                    "my_admin",
+                    "admin",
+                    true,
+                    vec![r#"feature = "backoffice""#.to_string()]
+                ),
+                ("unix_only", "unix_only", false, vec!["unix".to_string()]),
+            ]
+        );
+        assert!(diagnostics.warnings().is_empty());
+    }
+
+    #[test]
+    fn reads_workspace_inherited_dependencies() {
+        let dir = TestDir::new("workspace_dependencies");
+        dir.write(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["app"]
+
+            [workspace.dependencies]
+            api = { path = "crates/api" }
+            "#,
+        );
+        dir.write(
+            "app/Cargo.toml",
+            r#"
+            [package]
+            name = "app"
+
+            [dependencies]
+            api = { workspace = true }
+            missing = { workspace = true }
+            "#,
+        );
+
+        let diagnostics = Diagnostics::new();
+        let dependencies = read_path_dependencies(&dir.path().join("app"), &diagnostics).unwrap();
+        assert_eq!(dependencies.len(), 1);
+        assert_eq!(
+            dependencies[0].dir,
+            normalize(dir.path()).join("crates/api")
+        );
+        assert_eq!(diagnostics.warnings().len(), 1);
+        assert!(diagnostics.warnings()[0].contains("`missing`"));
+    }
+}
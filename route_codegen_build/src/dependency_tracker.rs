@@ -0,0 +1,79 @@
+use quote::quote;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 记录宏展开过程中读取过的所有文件（源码文件、Cargo.toml 等）
+///
+/// 过程宏直接从磁盘读取文件，cargo 并不知道这些依赖。
+/// 这里把每个文件通过隐藏的 `include_bytes!` 引入生成代码中，
+/// 让 rustc 把它们写进 dep-info，文件变化后 cargo 就会重新编译调用宏的 crate。
+///
+/// 新增的 handler 文件必须在已被追踪的父模块文件中用 `mod xxx;` 声明才能参与编译，
+/// 父模块文件的修改同样会触发重新展开，因此新文件总能在下一次 `cargo build` 中被发现。
+///
+/// 未引用文件的警告和 glob 形式的 workspace 成员取决于目录中有哪些文件，这些目录也被记录下来。
+/// build.rs 中为它们输出 `cargo:rerun-if-changed`（cargo 会检查整个目录）；
+/// 稳定版 Rust 的过程宏无法追踪目录，宏展开时目录中新增的文件要等到下一次重新展开才会被发现。
+#[derive(Debug, Default)]
+pub struct DependencyTracker {
+    files: Mutex<BTreeSet<PathBuf>>,
+    dirs: Mutex<BTreeSet<PathBuf>>, // 内容（有哪些文件）影响扫描结果的目录
+}
+
+impl DependencyTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 追踪单个文件；目录会追踪其中的 mod.rs（如果存在）
+    pub fn track<P: AsRef<Path>>(&self, path: P) {
+        let path = path.as_ref();
+        let file = if path.is_dir() {
+            path.join("mod.rs")
+        } else {
+            path.to_path_buf()
+        };
+        if !file.is_file() {
+            return;
+        }
+        let file = file.canonicalize().unwrap_or(file);
+        self.files.lock().unwrap().insert(file);
+    }
+
+    /// 追踪目录中文件的增删，不存在的目录被忽略
+    pub fn track_dir<P: AsRef<Path>>(&self, dir: P) {
+        let dir = dir.as_ref();
+        if !dir.is_dir() {
+            return;
+        }
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        self.dirs.lock().unwrap().insert(dir);
+    }
+
+    /// 已追踪的文件列表（有序，保证生成代码稳定）
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.files.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// 已追踪的目录列表（有序）
+    pub fn dirs(&self) -> Vec<PathBuf> {
+        self.dirs.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// 生成隐藏的 `include_bytes!` 常量，让 cargo 追踪这些文件
+    pub fn to_tokens(&self) -> proc_macro2::TokenStream {
+        let includes = self.files().into_iter().filter_map(|file| {
+            let file = file.to_str()?.to_string();
+            Some(quote! {
+                const _: &[u8] = include_bytes!(#file);
+            })
+        });
+
+        quote! {
+            const _: () = {
+                #(#includes)*
+            };
+        }
+    }
+}
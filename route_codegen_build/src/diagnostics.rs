@@ -0,0 +1,116 @@
+use quote::{format_ident, quote};
+use std::sync::Mutex;
+
+/// 宏展开过程中收集的诊断信息，同时负责输出扫描日志
+///
+/// 稳定版 Rust 的过程宏无法直接发出警告，这里借助 `#[deprecated]` 的技巧：
+/// 为每条警告生成一个被弃用的类型并立即使用它，rustc 会把 note 作为警告打印出来。
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    warnings: Mutex<Vec<String>>,
+    errors: Mutex<Vec<String>>,
+    quiet: bool,  // 不输出扫描日志（stdout 需要留给其他输出时使用）
+    strict: bool, // 扫描中遇到的问题作为错误而不是警告报告
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 是否输出扫描日志，警告和错误总会写到 stderr
+    pub fn quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+
+    /// 是否把扫描中遇到的问题（文件无法读取或解析、glob 无效、依赖无法解析等）作为错误报告
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// 输出一条扫描日志
+    pub fn info<S: AsRef<str>>(&self, message: S) {
+        if !self.quiet {
+            println!("{}", message.as_ref());
+        }
+    }
+
+    /// 记录一条警告
+    pub fn warn<S: Into<String>>(&self, message: S) {
+        let message = message.into();
+        eprintln!("⚠️ {}", message);
+        self.warnings.lock().unwrap().push(message);
+    }
+
+    /// 记录一条错误，最终以 `compile_error!` 的形式报告
+    pub fn error<S: Into<String>>(&self, message: S) {
+        let message = message.into();
+        eprintln!("❌ {}", message);
+        self.errors.lock().unwrap().push(message);
+    }
+
+    /// 记录扫描中遇到、可能导致路由丢失的问题：strict 模式下是错误，否则是警告
+    pub fn problem<S: Into<String>>(&self, message: S) {
+        if self.strict {
+            self.error(message);
+        } else {
+            self.warn(message);
+        }
+    }
+
+    /// 已记录的警告（排序后）
+    pub fn warnings(&self) -> Vec<String> {
+        // 诊断信息可能来自并行扫描，排序后输出以保证生成代码稳定
+        let mut warnings = self.warnings.lock().unwrap().clone();
+        warnings.sort();
+        warnings
+    }
+
+    /// 已记录的错误（排序后）
+    pub fn errors(&self) -> Vec<String> {
+        let mut errors = self.errors.lock().unwrap().clone();
+        errors.sort();
+        errors
+    }
+
+    /// 生成触发编译警告和编译错误的代码
+    pub fn to_tokens(&self) -> proc_macro2::TokenStream {
+        let errors = self.error_tokens();
+        let items = self
+            .warnings()
+            .into_iter()
+            .enumerate()
+            .map(|(index, message)| {
+                let ident = format_ident!("RouteCodegenWarning{}", index);
+                let note = format!("route_codegen: {}", message);
+                quote! {
+                    const _: () = {
+                        #[deprecated(note = #note)]
+                        struct #ident;
+                        let _ = #ident;
+                    };
+                }
+            });
+
+        quote! {
+            #errors
+            #(#items)*
+        }
+    }
+
+    /// 生成 `compile_error!` 报告所有错误
+    pub fn error_tokens(&self) -> proc_macro2::TokenStream {
+        let errors = self.errors().into_iter().map(|message| {
+            let message = format!("route_codegen: {}", message);
+            quote! {
+                compile_error!(#message);
+            }
+        });
+
+        quote! {
+            #(#errors)*
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use crate::codegen_attr::{parse_cfgs, parse_route_scope, RouteScope};
+use crate::configure_hook::parse_configure_hook;
+use crate::middleware::{parse_route_middleware, RouteMiddleware};
+use crate::module_fn::ModuleFn;
+use crate::openapi::{parse_schema_struct, SchemaStruct};
+use crate::provider::{parse_route_provider, RouteProvider};
+use crate::type_path::ModuleNames;
+use crate::{extract_route_info, RouteFunction};
+use serde::{Deserialize, Serialize};
+use syn::ext::IdentExt;
+use syn::{Attribute, Item, ItemFn, ItemMod};
+
+/// 单个源码文件中与路由有关的内容
+///
+/// 只包含从文件本身解析出的信息，模块路径、scope 和 cfg 等由模块树决定的字段
+/// 在解析模块树时填写，因此同一文件的解析结果可以缓存并在下一次扫描中复用。
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FileSummary {
+    pub scope: ScopeDeclaration, // 文件顶部的 #![route_scope(..)] 或 module_scope!(..)
+    pub cfgs: Vec<String>,       // 文件顶部的 #![cfg(..)]
+    pub items: Vec<ItemSummary>, // 按声明顺序排列的条目
+    pub names: ModuleNames,      // use 引入的名称和文件中定义的类型，用于解析类型路径
+}
+
+/// 模块的 scope 声明，声明无效时为错误
+pub type ScopeDeclaration = Result<Option<RouteScope>, SourceError>;
+
+/// 文件中某一行上的错误
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SourceError {
+    pub line: usize,     // 出错的行号
+    pub message: String, // 错误信息（不含文件路径）
+}
+
+/// 模块中与路由有关的条目
+#[derive(Clone, Serialize, Deserialize)]
+pub enum ItemSummary {
+    Route(RouteFunction),        // handler
+    Middleware(RouteMiddleware), // #[route_middleware]
+    Provider(RouteProvider),     // #[route_provider]
+    Hook(ModuleFn),              // #[configure_hook]
+    Schema(SchemaStruct),        // 带命名字段的结构体
+    Module(ModuleSummary),       // 内联模块或 `mod x;` 声明
+    Error(SourceError),          // 无效的路由属性或模块级函数签名
+}
+
+/// 子模块声明
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ModuleSummary {
+    pub name: String,                    // 模块名（去掉 r# 前缀）
+    pub line: usize,                     // 声明所在行号
+    pub path_attr: Option<String>,       // #[path = "..."] 属性
+    pub scope: ScopeDeclaration,         // 模块上的 scope 声明
+    pub cfgs: Vec<String>,               // 模块上的 #[cfg] 条件
+    pub items: Option<Vec<ItemSummary>>, // 内联模块的条目，`mod x;` 为 None
+    pub names: ModuleNames,              // 内联模块中 use 引入的名称和定义的类型
+}
+
+impl FileSummary {
+    pub fn new(file: &syn::File) -> Self {
+        Self {
+            scope: scope_declaration(&file.attrs, &file.items),
+            cfgs: parse_cfgs(&file.attrs),
+            items: summarize_items(&file.items),
+            names: ModuleNames::new(&file.items),
+        }
+    }
+
+    /// 文件中（包括内联模块中）是否存在 handler
+    pub fn has_routes(&self) -> bool {
+        fn contains_route(items: &[ItemSummary]) -> bool {
+            items.iter().any(|item| match item {
+                ItemSummary::Route(_) => true,
+                ItemSummary::Module(ModuleSummary {
+                    items: Some(items), ..
+                }) => contains_route(items),
+                _ => false,
+            })
+        }
+        contains_route(&self.items)
+    }
+}
+
+fn summarize_items(items: &[Item]) -> Vec<ItemSummary> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Fn(fn_item) => summarize_fn(fn_item),
+            Item::Struct(struct_item) => parse_schema_struct(struct_item).map(ItemSummary::Schema),
+            Item::Mod(module) => Some(ItemSummary::Module(summarize_module(module))),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 识别函数的种类：中间件、provider、configure hook 或 handler
+fn summarize_fn(fn_item: &ItemFn) -> Option<ItemSummary> {
+    let line = fn_item.sig.ident.span().start().line;
+    if let Some(middleware) = parse_route_middleware(fn_item) {
+        return Some(ItemSummary::Middleware(middleware));
+    }
+    match parse_route_provider(fn_item) {
+        Some(Ok(provider)) => return Some(ItemSummary::Provider(provider)),
+        Some(Err(message)) => return Some(ItemSummary::Error(SourceError { line, message })),
+        None => {}
+    }
+    match parse_configure_hook(fn_item) {
+        Some(Ok(hook)) => return Some(ItemSummary::Hook(hook)),
+        Some(Err(message)) => return Some(ItemSummary::Error(SourceError { line, message })),
+        None => {}
+    }
+    match extract_route_info(fn_item) {
+        Ok(route_fn) => route_fn.map(ItemSummary::Route),
+        Err(e) => Some(ItemSummary::Error(SourceError {
+            line: match e.span().start().line {
+                0 => line,
+                attr_line => attr_line,
+            },
+            message: format!("invalid route attribute on `{}`: {}", fn_item.sig.ident, e),
+        })),
+    }
+}
+
+fn summarize_module(module: &ItemMod) -> ModuleSummary {
+    // `mod x;` 上的 #[route_scope] 在稳定版中无法编译，这里仍然读取，文件内的声明会覆盖它
+    let inner_items = module
+        .content
+        .as_ref()
+        .map(|(_, items)| items.as_slice())
+        .unwrap_or_default();
+    ModuleSummary {
+        name: module.ident.unraw().to_string(),
+        line: module.ident.span().start().line,
+        path_attr: path_attribute(module),
+        scope: scope_declaration(&module.attrs, inner_items),
+        cfgs: parse_cfgs(&module.attrs),
+        items: module
+            .content
+            .as_ref()
+            .map(|(_, items)| summarize_items(items)),
+        names: ModuleNames::new(inner_items),
+    }
+}
+
+fn scope_declaration(attrs: &[Attribute], items: &[Item]) -> ScopeDeclaration {
+    parse_route_scope(attrs, items).map_err(|e| SourceError {
+        line: e.span().start().line,
+        message: e.to_string(),
+    })
+}
+
+/// 读取模块上的 `#[path = "..."]` 属性
+fn path_attribute(module: &ItemMod) -> Option<String> {
+    module.attrs.iter().find_map(|attr| {
+        if !attr.path().is_ident("path") {
+            return None;
+        }
+        match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(s),
+                        ..
+                    }),
+                ..
+            }) => Some(s.value()),
+            _ => None,
+        }
+    })
+}
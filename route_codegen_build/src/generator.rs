@@ -0,0 +1,500 @@
+use crate::configure_builder::{
+    build_configure_function, generate_configure_functions_and_routes, group_functions_by_module,
+    CodegenOptions, ModuleGroup, ScopeStrategy,
+};
+use crate::dependency_tracker::DependencyTracker;
+use crate::diagnostics::Diagnostics;
+use crate::manifest::{build_route_manifest, route_entries, RouteEntry};
+use crate::module_fn::ModuleFns;
+use crate::openapi::{build_openapi, PackageInfo, SchemaStruct};
+use crate::provider::check_providers;
+use crate::registration::{build_static_configure, linked_crates, Registration};
+use crate::route_check::check_routes;
+use crate::scan_cache::ScanCache;
+use crate::type_path::{ModuleNames, TypeResolver};
+use crate::workspace::{normalize, workspace_root};
+use crate::{
+    build_scan_rules, log_found_functions, log_scan_rules, normalize_path, read_package_name,
+    read_package_version, read_prefix_rules, scan_crate_for_route_functions, ScanContext,
+    ScanResult,
+};
+use quote::quote;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 路由代码生成器：扫描 crate 及其 path 依赖中的 handler，
+/// 生成 `configure`、`ROUTES` 和 `openapi_json()`。
+///
+/// 过程宏 `generate_configure!` 使用 [`Generator::expand`]，
+/// build.rs 使用 [`Generator::write_to`] 把同样的代码写入文件。
+///
+/// 使用 [`Registration::Static`] 时不扫描源码，生成的 `configure` 和 `ROUTES`
+/// 在运行时遍历通过 `#[route_codegen::register]` 登记的 handler。
+#[derive(Clone, Debug, Default)]
+pub struct Generator {
+    manifest_dir: Option<PathBuf>, // 被扫描 crate 的目录，默认读取 CARGO_MANIFEST_DIR
+    invoked_from: Option<PathBuf>, // 调用宏的源码文件，用于确定生成代码所在的编译目标
+    patterns: Vec<String>,         // glob 规则（相对 manifest_dir），以 ! 开头表示排除
+    quiet: bool,                   // 不向 stdout 输出扫描日志
+    strict: bool,                  // 扫描中遇到的问题作为错误报告
+    no_cache: bool,                // 不读写 target 目录中的扫描缓存
+    registration: Registration,    // 扫描源码，或遍历链接时登记的 handler
+    options: CodegenOptions,       // 入口函数名、scope 策略、公共前缀、前缀映射规则
+    invalid_mounts: Vec<String>,   // 无效的挂载前缀，生成时报告
+    external_data: Vec<String>,    // 由手写代码注册的 app data 类型，不要求 provider
+}
+
+/// 一次扫描的结果：按注册顺序排列的模块分组
+struct Scanned {
+    manifest_dir: String,
+    groups: Vec<ModuleGroup>,
+    schemas: BTreeMap<String, SchemaStruct>,
+    modules: BTreeMap<String, ModuleNames>,
+    module_fns: ModuleFns,
+    tracker: DependencyTracker,
+    diagnostics: Diagnostics,
+}
+
+/// 一次扫描的生成结果
+struct Generated {
+    code: proc_macro2::TokenStream, // configure / ROUTES / openapi_json
+    tracker: DependencyTracker,
+    diagnostics: Diagnostics,
+}
+
+impl Generator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 指定被扫描 crate 的目录（Cargo.toml 所在目录）
+    pub fn manifest_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.manifest_dir = Some(dir.into());
+        self
+    }
+
+    /// 生成的代码所在的源码文件（如 `src/bin/admin.rs`），从它所属编译目标的根文件开始扫描；
+    /// 默认依次使用 src/main.rs、库目标和第一个可执行目标
+    pub fn invoked_from<P: Into<PathBuf>>(mut self, file: P) -> Self {
+        self.invoked_from = Some(file.into());
+        self
+    }
+
+    /// 只保留匹配 glob 规则的文件中的 handler；以 `!` 开头的规则表示排除
+    pub fn include<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.patterns.push(pattern.into());
+        self
+    }
+
+    /// 生成的入口函数名，默认 `configure`
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.options.name = name.into();
+        self
+    }
+
+    /// scope 的生成策略，默认每个模块一个 scope
+    pub fn scope(mut self, scope: ScopeStrategy) -> Self {
+        self.options.scope = scope;
+        self
+    }
+
+    /// 所有路由的公共前缀（如 `/api`）
+    pub fn prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.options.prefix = prefix.into();
+        self
+    }
+
+    /// 去掉模块路径开头的模块（如 `handler`、`api` 或 `api_tool::api`），
+    /// 设置后替换 `[package.metadata.route_codegen]` 中的 `strip`
+    pub fn strip<S: Into<String>>(mut self, module: S) -> Self {
+        self.options
+            .rules
+            .strip
+            .get_or_insert_with(Vec::new)
+            .push(module.into());
+        self
+    }
+
+    /// 合并重复的相邻模块段，如 `agency/agency_api` -> `agency`
+    pub fn collapse_duplicates(mut self, collapse: bool) -> Self {
+        self.options.rules.collapse_duplicates = Some(collapse);
+        self
+    }
+
+    /// 把模块名中的 `_` 转换为 `-`，如 `tool_info` -> `tool-info`
+    pub fn kebab_case(mut self, kebab_case: bool) -> Self {
+        self.options.rules.kebab_case = Some(kebab_case);
+        self
+    }
+
+    /// 把依赖 crate 挂载到指定前缀（如 `/tools`），空字符串表示挂在根上；
+    /// 默认挂载在 `/<crate 名称>`
+    pub fn mount<C: AsRef<str>, P: AsRef<str>>(mut self, crate_name: C, prefix: P) -> Self {
+        if let Err(e) = self
+            .options
+            .rules
+            .set_mount(crate_name.as_ref(), prefix.as_ref())
+        {
+            self.invalid_mounts.push(e);
+        }
+        self
+    }
+
+    /// 排除匹配 glob 规则的文件中的 handler
+    pub fn exclude<S: AsRef<str>>(mut self, pattern: S) -> Self {
+        self.patterns.push(format!("!{}", pattern.as_ref()));
+        self
+    }
+
+    /// 不向 stdout 输出扫描日志，警告和错误仍输出到 stderr
+    pub fn quiet(mut self) -> Self {
+        self.quiet = true;
+        self
+    }
+
+    /// strict 模式：无法读取或解析的文件、无效的 glob、无法解析的依赖等
+    /// 都作为错误报告；默认模式下报告为警告，扫描继续进行
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// 是否把文件的解析结果缓存到 target 目录（build.rs 中为 `OUT_DIR`），默认开启；
+    /// 关闭后每次扫描都重新解析所有文件
+    pub fn cache(mut self, cache: bool) -> Self {
+        self.no_cache = !cache;
+        self
+    }
+
+    /// 路由的登记方式，默认扫描源码
+    ///
+    /// [`Registration::Static`] 不读取源码，只有标注了 `#[route_codegen::register]` 的 handler
+    /// 会被注册，前缀只由模块路径和映射规则决定：不支持 glob 规则、`scope = "nested"`、
+    /// `#[route_scope]` / `module_scope!`、中间件、provider 和 configure hook，也不生成 `openapi_json()`。
+    pub fn registration(mut self, registration: Registration) -> Self {
+        self.registration = registration;
+        self
+    }
+
+    /// 声明由手写代码（如 `App::app_data`）注册的 `web::Data<T>` 类型，提取它的 handler 不要求 provider
+    ///
+    /// 类型按调用宏的 crate 的根模块解析（如 `crate::config::AppConfig`），只写名称时按名称比较。
+    pub fn external_data<S: Into<String>>(mut self, ty: S) -> Self {
+        self.external_data.push(ty.into());
+        self
+    }
+
+    /// 扫描 crate，按注册顺序返回完整的路由表
+    ///
+    /// 无论使用哪种登记方式都通过扫描计算；strict 模式下扫描产生任何错误都会返回 Err。
+    pub fn routes(&self) -> Result<Vec<RouteEntry>, String> {
+        let scanned = self.scan()?;
+        let errors = scanned.diagnostics.errors();
+        if self.strict && !errors.is_empty() {
+            return Err(errors.join("\n"));
+        }
+        let manifest_path = PathBuf::from(&scanned.manifest_dir).join("Cargo.toml");
+        let crate_name = read_package_name(&manifest_path)
+            .map(|name| name.replace('-', "_"))
+            .unwrap_or_default();
+        Ok(route_entries(
+            &scanned.groups,
+            &scanned.manifest_dir,
+            &crate_name,
+        ))
+    }
+
+    /// 生成过程宏的展开结果：路由代码、依赖追踪，以及以编译警告 / 错误形式报告的诊断信息
+    pub fn expand(&self) -> proc_macro2::TokenStream {
+        let generated = match self.generate() {
+            Ok(generated) => generated,
+            Err(e) => {
+                return syn::Error::new(
+                    proc_macro2::Span::call_site(),
+                    format!("Failed to scan crate for route functions: {}", e),
+                )
+                .to_compile_error();
+            }
+        };
+
+        let code = generated.code;
+        let tracked = generated.tracker.to_tokens();
+        let warnings = generated.diagnostics.to_tokens();
+        quote! {
+            #code
+            #tracked
+            #warnings
+        }
+    }
+
+    /// 把生成的代码写入 `out_dir/routes.rs`，返回写入的文件路径
+    ///
+    /// 供 build.rs 使用：每个扫描过的文件和影响扫描结果的目录都会输出 `cargo:rerun-if-changed`，
+    /// 警告通过 `cargo:warning` 输出，错误以 `compile_error!` 写入生成的文件。
+    /// 生成的文件需要在 crate 根部 `include!`，handler 路径相对于 crate 根解析。
+    pub fn write_to<P: AsRef<Path>>(&self, out_dir: P) -> io::Result<PathBuf> {
+        let generated = self.generate().map_err(io::Error::other)?;
+
+        for file in generated.tracker.files() {
+            println!("cargo:rerun-if-changed={}", file.display());
+        }
+        for dir in generated.tracker.dirs() {
+            println!("cargo:rerun-if-changed={}", dir.display());
+        }
+        for warning in generated.diagnostics.warnings() {
+            println!("cargo:warning=route_codegen: {}", warning);
+        }
+
+        let code = generated.code;
+        let errors = generated.diagnostics.error_tokens();
+        let file: syn::File = syn::parse2(quote! {
+            #errors
+            #code
+        })
+        .map_err(|e| io::Error::other(format!("generated code is not valid Rust: {}", e)))?;
+
+        let content = format!(
+            "// @generated by route_codegen_build, do not edit\n\n{}",
+            prettyplease::unparse(&file)
+        );
+        let path = out_dir.as_ref().join("routes.rs");
+        // 内容不变时不重写文件，避免触发不必要的重新编译
+        if fs::read_to_string(&path).ok().as_deref() != Some(content.as_str()) {
+            fs::write(&path, content)?;
+        }
+        Ok(path)
+    }
+
+    /// 被扫描 crate 的目录
+    fn resolve_manifest_dir(&self) -> Result<String, String> {
+        if let Some(e) = self.invalid_mounts.first() {
+            return Err(e.clone());
+        }
+        match &self.manifest_dir {
+            Some(dir) => Ok(dir.to_string_lossy().into_owned()),
+            None => std::env::var("CARGO_MANIFEST_DIR")
+                .map_err(|_| "CARGO_MANIFEST_DIR environment variable not found".to_string()),
+        }
+    }
+
+    /// 合并 Cargo.toml 中的映射规则，显式指定的规则优先
+    fn codegen_options(&self, manifest_dir: &str, diagnostics: &Diagnostics) -> CodegenOptions {
+        let mut options = self.options.clone();
+        match read_prefix_rules(&PathBuf::from(manifest_dir).join("Cargo.toml")) {
+            Ok(rules) => options.rules = options.rules.or(rules),
+            Err(e) => diagnostics.problem(e),
+        }
+        options
+    }
+
+    /// 扫描 crate 中的路由函数，分组排序并检查冲突
+    fn scan(&self) -> Result<Scanned, String> {
+        let manifest_dir = self.resolve_manifest_dir()?;
+
+        let tracker = DependencyTracker::new();
+        let diagnostics = Diagnostics::new().quiet(self.quiet).strict(self.strict);
+
+        let cache = if self.no_cache {
+            ScanCache::in_memory()
+        } else {
+            ScanCache::open(Path::new(&manifest_dir))
+        };
+
+        let scan_rules = if self.patterns.is_empty() {
+            None
+        } else {
+            let scan_rules = build_scan_rules(&self.patterns, &diagnostics);
+            log_scan_rules(&scan_rules, &diagnostics);
+            Some(scan_rules)
+        };
+
+        let ScanResult {
+            mut functions,
+            schemas,
+            mut module_fns,
+            modules,
+        } = scan_crate_for_route_functions(
+            &manifest_dir,
+            self.invoked_from.as_deref(),
+            ScanContext {
+                tracker: &tracker,
+                cache: &cache,
+                diagnostics: &diagnostics,
+            },
+        )?;
+        cache.save(&diagnostics);
+
+        // 指定了 glob 规则时，只保留匹配文件中的路由函数和 configure hook
+        if let Some(rules) = &scan_rules {
+            let included = |source_file: &Path| {
+                let rel_path = source_file
+                    .strip_prefix(&manifest_dir)
+                    .unwrap_or(source_file);
+                rules.should_include(&normalize_path(&rel_path))
+            };
+            functions.retain(|f| included(&f.source_file));
+            module_fns.hooks.retain(|hook| included(&hook.source_file));
+        }
+
+        let options = self.codegen_options(&manifest_dir, &diagnostics);
+        if options.scope == ScopeStrategy::Flat {
+            for middleware in &module_fns.middlewares {
+                diagnostics.warn(format!(
+                    "{}: #[route_middleware] `{}` is ignored with scope = \"flat\"; use \"module\" or \"nested\"",
+                    middleware.function.location(),
+                    middleware.function.name
+                ));
+            }
+        }
+        let groups = group_functions_by_module(&functions, &module_fns, &options, &diagnostics);
+        log_found_functions(&groups, &diagnostics);
+        check_routes(&groups, &options, &diagnostics);
+        check_providers(
+            &groups,
+            &module_fns.providers,
+            &self.external_data,
+            &TypeResolver::new(&modules),
+            &options,
+            &diagnostics,
+        );
+
+        Ok(Scanned {
+            manifest_dir,
+            groups,
+            schemas,
+            modules,
+            module_fns,
+            tracker,
+            diagnostics,
+        })
+    }
+
+    /// 生成路由代码
+    fn generate(&self) -> Result<Generated, String> {
+        if syn::parse_str::<syn::Ident>(&self.options.name).is_err() {
+            return Err(format!(
+                "`{}` is not a valid function name",
+                self.options.name
+            ));
+        }
+        let generated = match self.registration {
+            Registration::Scan => self.generate_scanned()?,
+            Registration::Static => self.generate_static()?,
+        };
+
+        // 命名的路由集合：生成代码放在同名模块中，入口函数重新导出到调用位置，
+        // ROUTES 和 openapi_json 通过 `<name>::ROUTES`、`<name>::openapi_json()` 访问
+        let code = generated.code;
+        let code = if self.options.in_module() {
+            let module = syn::Ident::new(&self.options.name, proc_macro2::Span::call_site());
+            let doc = format!("路由集合 `{}` 的生成代码", self.options.name);
+            quote! {
+                #[doc = #doc]
+                pub mod #module {
+                    #code
+                }
+                pub use #module::#module;
+            }
+        } else {
+            code
+        };
+
+        Ok(Generated { code, ..generated })
+    }
+
+    /// 扫描 crate，生成每个 handler 的注册代码
+    fn generate_scanned(&self) -> Result<Generated, String> {
+        let scanned = self.scan()?;
+        let (all_configure_fns, all_configure_calls, all_routes) =
+            generate_configure_functions_and_routes(
+                &scanned.groups,
+                &scanned.module_fns,
+                &self.options,
+            );
+
+        let configure = build_configure_function(
+            all_configure_fns,
+            all_configure_calls,
+            all_routes,
+            &self.options,
+        );
+        let manifest = build_route_manifest(&scanned.groups, &scanned.manifest_dir);
+        let manifest_path = PathBuf::from(&scanned.manifest_dir).join("Cargo.toml");
+        let package = PackageInfo {
+            name: read_package_name(&manifest_path).unwrap_or_default(),
+            version: read_package_version(&manifest_path).unwrap_or_else(|e| {
+                scanned.diagnostics.problem(e);
+                "0.0.0".to_string()
+            }),
+        };
+        let openapi = build_openapi(
+            &scanned.groups,
+            &scanned.schemas,
+            &scanned.modules,
+            &package,
+        );
+
+        let code = quote! {
+            #configure
+            #manifest
+            #openapi
+        };
+        Ok(Generated {
+            code,
+            tracker: scanned.tracker,
+            diagnostics: scanned.diagnostics,
+        })
+    }
+
+    /// 静态注册：不扫描源码，只读取 Cargo.toml 中的映射规则
+    fn generate_static(&self) -> Result<Generated, String> {
+        let manifest_dir = self.resolve_manifest_dir()?;
+        if !self.patterns.is_empty() {
+            return Err(
+                "include/exclude patterns are not supported with registration = \"static\"; \
+                 only handlers marked with #[route_codegen::register] are registered"
+                    .to_string(),
+            );
+        }
+        if self.options.scope == ScopeStrategy::Nested {
+            return Err(
+                "scope = \"nested\" is not supported with registration = \"static\"; \
+                 use \"module\" or \"flat\""
+                    .to_string(),
+            );
+        }
+
+        let tracker = DependencyTracker::new();
+        let diagnostics = Diagnostics::new().quiet(self.quiet).strict(self.strict);
+        tracker.track(PathBuf::from(&manifest_dir).join("Cargo.toml"));
+        let options = self.codegen_options(&manifest_dir, &diagnostics);
+        diagnostics.info("📌 Static registration: handlers marked with #[route_codegen::register] are collected at link time");
+
+        // file!() 相对 workspace 根，去掉被扫描 crate 的目录后与扫描结果中的文件路径一致
+        let crate_dir = normalize(Path::new(&manifest_dir));
+        let file_prefix = workspace_root(&crate_dir)
+            .and_then(|root| {
+                crate_dir
+                    .strip_prefix(&root)
+                    .ok()
+                    .map(|dir| normalize_path(&dir).into_owned())
+            })
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| format!("{}/", dir))
+            .unwrap_or_default();
+
+        let linked = linked_crates(
+            Path::new(&manifest_dir),
+            self.invoked_from.as_deref(),
+            &tracker,
+            &diagnostics,
+        );
+        Ok(Generated {
+            code: build_static_configure(&options, &file_prefix, &linked),
+            tracker,
+            diagnostics,
+        })
+    }
+}
@@ -0,0 +1,552 @@
+//! route_codegen 的扫描器和代码生成器
+//!
+//! 既供 `route_codegen::generate_configure!` 过程宏在宏展开时调用，
+//! 也可以在 `build.rs` 中通过 [`Generator`] 把生成的路由代码写入 `OUT_DIR`：
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     let out_dir = std::env::var("OUT_DIR").unwrap();
+//!     route_codegen_build::Generator::new()
+//!         .include("**/src/**/*.rs")
+//!         .write_to(out_dir)
+//!         .expect("failed to generate routes");
+//! }
+//!
+//! // main.rs（必须在 crate 根部 include）
+//! include!(concat!(env!("OUT_DIR"), "/routes.rs"));
+//! ```
+
+mod codegen_attr;
+mod configure_args;
+mod configure_builder;
+mod configure_hook;
+mod crate_targets;
+mod dependencies;
+mod dependency_tracker;
+mod diagnostics;
+mod file_summary;
+mod generator;
+mod manifest;
+mod middleware;
+mod module_fn;
+mod module_resolver;
+mod openapi;
+mod prefix_rules;
+mod provider;
+mod registration;
+mod route_attr;
+mod route_check;
+mod route_order;
+mod scan_cache;
+mod scope_tree;
+#[cfg(test)]
+mod test_dir;
+mod tools;
+mod type_path;
+mod workspace;
+
+pub use crate::codegen_attr::RouteScope;
+pub use crate::configure_args::{find_invocations, ConfigureArgs, Invocation};
+pub use crate::configure_builder::ScopeStrategy;
+pub use crate::generator::Generator;
+pub use crate::manifest::RouteEntry;
+pub use crate::registration::{check_marker_below_register, expand_register, Registration};
+
+use crate::codegen_attr::{is_skipped, parse_cfgs, parse_priority, ScopeAnchor};
+use crate::configure_builder::ModuleGroup;
+use crate::crate_targets::CrateTargets;
+use crate::dependencies::{read_path_dependencies, PathDependency};
+use crate::dependency_tracker::DependencyTracker;
+use crate::diagnostics::Diagnostics;
+use crate::module_fn::ModuleFns;
+use crate::module_resolver::{report_orphan_route_files, ModuleResolver};
+use crate::openapi::{parse_handler_signature, HandlerSignature, SchemaStruct};
+use crate::prefix_rules::PrefixRules;
+use crate::route_attr::{parse_route_attributes, RouteAttribute};
+use crate::scan_cache::ScanCache;
+use crate::type_path::ModuleNames;
+use crate::workspace::{read_workspace_config, workspace_root};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use syn::ItemFn;
+
+// 构建扫描规则，无效的 glob 规则会被报告并忽略
+fn build_scan_rules(patterns: &[String], diagnostics: &Diagnostics) -> ScanRules {
+    let (mut include_patterns, exclude_patterns) = split_include_exclude(patterns);
+    // 只有排除规则时，默认包含所有文件
+    if include_patterns.is_empty() {
+        include_patterns.push("**".to_string());
+    }
+    let include_set = build_glob_set(&include_patterns, diagnostics);
+    let exclude_set = build_glob_set(&exclude_patterns, diagnostics);
+
+    ScanRules {
+        include: include_set,
+        exclude: exclude_set,
+        include_patterns,
+        exclude_patterns,
+    }
+}
+
+// 打印扫描规则
+fn log_scan_rules(rules: &ScanRules, diagnostics: &Diagnostics) {
+    diagnostics.info("🎯 Scan Rules:");
+    diagnostics.info("✅ Include patterns:");
+    for pattern in &rules.include_patterns {
+        diagnostics.info(format!(" - {}", pattern));
+    }
+
+    diagnostics.info("❌ Exclude patterns:");
+    for pattern in &rules.exclude_patterns {
+        diagnostics.info(format!(" - {}", pattern));
+    }
+}
+
+// 打印找到的路由函数
+fn log_found_functions(grouped: &[ModuleGroup], diagnostics: &Diagnostics) {
+    let count: usize = grouped.iter().map(|group| group.functions.len()).sum();
+    diagnostics.info(format!("🔍 Found {} route functions", count));
+    for func in grouped.iter().flat_map(|group| &group.functions) {
+        for (method, path) in func.endpoints() {
+            diagnostics.info(format!(
+                " - {} [{} {}] (module: {:?})",
+                func.name, method, path, func.module_prefix
+            ));
+        }
+    }
+}
+
+fn split_include_exclude(patterns: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut include = Vec::new();
+    let mut exclude = Vec::new();
+
+    for pattern in patterns {
+        if let Some(stripped) = pattern.strip_prefix('!') {
+            exclude.push(stripped.to_string());
+        } else {
+            include.push(pattern.clone());
+        }
+    }
+
+    (include, exclude)
+}
+
+fn build_glob_set(patterns: &[String], diagnostics: &Diagnostics) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        match Glob::new(pattern) {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(e) => diagnostics.problem(format!("invalid glob pattern `{}`: {}", pattern, e)),
+        }
+    }
+    builder.build().unwrap_or_else(|e| {
+        diagnostics.problem(format!("failed to build glob set: {}", e));
+        GlobSet::empty()
+    })
+}
+
+#[derive(Debug)]
+struct ScanRules {
+    include: GlobSet,
+    exclude: GlobSet,
+    include_patterns: Vec<String>, // 新增字段
+    exclude_patterns: Vec<String>, // 新增字段
+}
+
+impl ScanRules {
+    fn should_include(&self, path: &str) -> bool {
+        self.include.is_match(path) && !self.exclude.is_match(path)
+    }
+}
+
+/// 扫描结果：路由函数、中间件等模块级函数，以及生成 OpenAPI schema 所需的结构体定义
+#[derive(Default)]
+struct ScanResult {
+    functions: Vec<RouteFunction>,
+    schemas: BTreeMap<String, SchemaStruct>,
+    module_fns: ModuleFns,
+    modules: BTreeMap<String, ModuleNames>, // 可达模块中的名称，用于解析类型路径
+}
+
+/// 一次扫描中共享的状态
+#[derive(Clone, Copy)]
+struct ScanContext<'a> {
+    tracker: &'a DependencyTracker, // 读取过的文件，登记为编译依赖
+    cache: &'a ScanCache,           // 文件解析结果的缓存
+    diagnostics: &'a Diagnostics,   // 诊断信息和扫描日志
+}
+
+/// 扫描 crate（manifest_dir 为其 Cargo.toml 所在目录）中所有的路由函数
+///
+/// `invoked_from` 为调用宏的源码文件，用于确定生成代码所在的编译目标。
+fn scan_crate_for_route_functions(
+    manifest_dir: &str,
+    invoked_from: Option<&Path>,
+    scan: ScanContext,
+) -> Result<ScanResult, String> {
+    let mut result = ScanResult::default();
+    let manifest_path = PathBuf::from(manifest_dir).join("Cargo.toml");
+    scan.tracker.track(&manifest_path);
+
+    // 扫描主项目，使用 "crate" 作为根，以及它依赖的 path crate
+    if read_package_name(&manifest_path).is_ok() {
+        match CrateTargets::read(Path::new(manifest_dir)) {
+            Ok(targets) => match targets.local_root(invoked_from, scan.tracker, scan.cache) {
+                Some(root_file) => {
+                    scan_project(manifest_dir, "crate", &root_file, &[], &mut result, scan)?
+                }
+                None => scan.diagnostics.problem(format!(
+                    "{} has no library or binary target; its handlers are not scanned",
+                    manifest_dir
+                )),
+            },
+            Err(e) => scan.diagnostics.problem(e),
+        }
+        match read_path_dependencies(Path::new(manifest_dir), scan.diagnostics) {
+            Ok(dependencies) => scan_dependencies(dependencies, &mut result, scan)?,
+            Err(e) => scan.diagnostics.problem(e),
+        }
+        return Ok(result);
+    }
+
+    // 虚拟 workspace 的根没有 [package]（如在 workspace 根运行 cargo routes），
+    // 扫描所有成员，设置了 default-members 时只扫描默认成员
+    let workspace_dir = Path::new(manifest_dir);
+    match read_workspace_config(workspace_dir) {
+        Ok(Some(workspace)) => {
+            let members = workspace.member_dirs(workspace_dir, scan.tracker, scan.diagnostics);
+            for member_dir in members {
+                let member = normalize_path(
+                    &member_dir
+                        .strip_prefix(workspace_dir)
+                        .unwrap_or(&member_dir),
+                )
+                .into_owned();
+                scan_library(
+                    &member_dir,
+                    &format!("workspace member `{}`", member),
+                    |package, lib_name| lib_name.unwrap_or(package).replace('-', "_"),
+                    &[],
+                    &mut result,
+                    scan,
+                )?;
+            }
+        }
+        Ok(None) => {}
+        Err(e) => scan.diagnostics.problem(e),
+    }
+
+    Ok(result)
+}
+
+/// 扫描被扫描 crate 的 path 依赖（包括 workspace 之外的目录），
+/// 依赖的 handler 通过被扫描 crate 中使用的名称引用
+fn scan_dependencies(
+    dependencies: Vec<PathDependency>,
+    result: &mut ScanResult,
+    scan: ScanContext,
+) -> Result<(), String> {
+    for dependency in dependencies {
+        scan_library(
+            &dependency.dir,
+            &format!("dependency `{}`", dependency.key),
+            |_, lib_name| dependency.crate_name(lib_name),
+            &dependency.cfgs,
+            result,
+            scan,
+        )?;
+    }
+    Ok(())
+}
+
+/// 扫描依赖或 workspace 成员的库目标
+///
+/// `crate_name` 根据包名和 `[lib] name` 计算被扫描 crate 中引用它使用的名称；
+/// `cfgs` 为启用它所需的条件，复制到其中所有 handler 的注册代码上。
+fn scan_library(
+    dir: &Path,
+    label: &str,
+    crate_name: impl FnOnce(&str, Option<&str>) -> String,
+    cfgs: &[String],
+    result: &mut ScanResult,
+    scan: ScanContext,
+) -> Result<(), String> {
+    let manifest_path = dir.join("Cargo.toml");
+    if !manifest_path.is_file() {
+        scan.diagnostics.problem(format!(
+            "{} could not be resolved: {} does not exist",
+            label,
+            manifest_path.display()
+        ));
+        return Ok(());
+    }
+    scan.tracker.track(&manifest_path);
+
+    let targets = read_package_name(&manifest_path)
+        .and_then(|package_name| Ok((package_name, CrateTargets::read(dir)?)));
+    match targets {
+        // 过程宏 crate 不能导出 handler
+        Ok((
+            _,
+            CrateTargets {
+                proc_macro: true, ..
+            },
+        )) => scan
+            .diagnostics
+            .info(format!("⏭️ Skipping {}: proc-macro crate", label)),
+        Ok((
+            package_name,
+            CrateTargets {
+                lib: Some(lib),
+                lib_name,
+                ..
+            },
+        )) => {
+            let crate_name = crate_name(&package_name, lib_name.as_deref());
+            scan_project(
+                &dir.to_string_lossy(),
+                &crate_name,
+                &lib,
+                cfgs,
+                result,
+                scan,
+            )?;
+        }
+        // 只有可执行目标的 crate（如命令行工具）无法被引用，不需要扫描
+        Ok(_) => scan
+            .diagnostics
+            .info(format!("⏭️ Skipping {}: no library target", label)),
+        Err(e) => scan.diagnostics.problem(format!("{}: {}", label, e)),
+    }
+    Ok(())
+}
+
+/// 读取并解析 Cargo.toml
+fn read_cargo_toml(manifest_path: &Path) -> Result<HashMap<String, toml::Value>, String> {
+    let mut file = fs::File::open(manifest_path)
+        .map_err(|e| format!("failed to open {}: {}", manifest_path.display(), e))?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)
+        .map_err(|e| format!("failed to read {}: {}", manifest_path.display(), e))?;
+
+    toml::from_str(&contents).map_err(|e| {
+        format!(
+            "failed to parse {}: {}",
+            manifest_path.display(),
+            e.to_string().trim_end()
+        )
+    })
+}
+
+// 读取 Cargo.toml 中的包名
+fn read_package_name(manifest_path: &Path) -> Result<String, String> {
+    let cargo_toml = read_cargo_toml(manifest_path)?;
+    cargo_toml
+        .get("package")
+        .and_then(|package| package.get("name"))
+        .and_then(|name| name.as_str())
+        .map(|name| name.to_string())
+        .ok_or_else(|| format!("{} has no `package.name`", manifest_path.display()))
+}
+
+/// 读取 Cargo.toml 中的 `package.version`，`version.workspace = true` 时读取 workspace 根目录的
+/// `[workspace.package]`；没有写版本时与 Cargo 相同，为 0.0.0
+fn read_package_version(manifest_path: &Path) -> Result<String, String> {
+    let cargo_toml = read_cargo_toml(manifest_path)?;
+    let Some(version) = cargo_toml
+        .get("package")
+        .and_then(|package| package.get("version"))
+    else {
+        return Ok("0.0.0".to_string());
+    };
+    if let Some(version) = version.as_str() {
+        return Ok(version.to_string());
+    }
+    let inherited = version
+        .get("workspace")
+        .and_then(|workspace| workspace.as_bool())
+        == Some(true);
+    let root = manifest_path.parent().and_then(workspace_root);
+    match root {
+        Some(root) if inherited => read_cargo_toml(&root.join("Cargo.toml"))?
+            .get("workspace")
+            .and_then(|workspace| workspace.get("package"))
+            .and_then(|package| package.get("version"))
+            .and_then(|version| version.as_str())
+            .map(|version| version.to_string())
+            .ok_or_else(|| {
+                format!(
+                    "{} inherits `package.version` but the workspace has no `workspace.package.version`",
+                    manifest_path.display()
+                )
+            }),
+        _ => Err(format!(
+            "{}: invalid `package.version`",
+            manifest_path.display()
+        )),
+    }
+}
+
+/// 读取 Cargo.toml 中 `[package.metadata.route_codegen]` 的前缀映射规则，未配置时返回默认规则
+fn read_prefix_rules(manifest_path: &Path) -> Result<PrefixRules, String> {
+    let cargo_toml = read_cargo_toml(manifest_path)?;
+    match cargo_toml
+        .get("package")
+        .and_then(|package| package.get("metadata"))
+        .and_then(|metadata| metadata.get("route_codegen"))
+    {
+        Some(metadata) => PrefixRules::from_metadata(metadata)
+            .map_err(|e| format!("{}: {}", manifest_path.display(), e)),
+        None => Ok(PrefixRules::default()),
+    }
+}
+
+/// 从编译目标的根文件出发，沿模块树扫描项目中的所有路由函数
+///
+/// 根文件顶层的 handler 同样会被注册，包括调用 `generate_configure!` 的 main.rs；
+/// 扫描只读取源码文本，不会看到宏自身的展开结果。
+fn scan_project(
+    manifest_dir: &str,
+    crate_root: &str,
+    root_file: &Path,
+    cfgs: &[String],
+    result: &mut ScanResult,
+    scan: ScanContext,
+) -> Result<(), String> {
+    let src_path = PathBuf::from(manifest_dir).join("src");
+    scan.diagnostics.info(format!(
+        "📦 Scanning manifest_dir: {:?} (root: {:?})",
+        manifest_dir,
+        root_file.strip_prefix(manifest_dir).unwrap_or(root_file)
+    ));
+
+    let resolver = ModuleResolver::new(scan.tracker, scan.cache, scan.diagnostics);
+    let functions = resolver.resolve_crate(root_file, crate_root, cfgs);
+    result.functions.extend(functions);
+    let module_fns = resolver.module_fns();
+    result.module_fns.middlewares.extend(module_fns.middlewares);
+    result.module_fns.providers.extend(module_fns.providers);
+    result.module_fns.hooks.extend(module_fns.hooks);
+    result.modules.extend(resolver.module_names());
+    for schema in resolver.schemas() {
+        result.schemas.entry(schema.path()).or_insert(schema);
+    }
+    // src/bin 中的目标和自定义路径的库不与 src 下的其他文件共享模块树，不检查未引用的文件
+    if root_file.parent() == Some(src_path.as_path()) {
+        report_orphan_route_files(
+            &src_path,
+            &resolver.visited_files(),
+            scan.tracker,
+            scan.cache,
+            scan.diagnostics,
+        );
+    }
+    Ok(())
+}
+
+/// 表示一个发现的路由函数的信息
+#[derive(Clone, Serialize, Deserialize)]
+struct RouteFunction {
+    name: String,                      // 函数名称
+    routes: Vec<RouteAttribute>,       // 路由属性（方法、路径、name/guard/wrap 等）
+    module_prefix: String,             // 新增字段：模块生成的路由前缀
+    crate_root: String,                // 所属 crate 的路径根（调用宏的 crate 为 "crate"）
+    source_file: PathBuf,              // 定义该函数的源码文件
+    line: usize,                       // 函数所在行号
+    priority: i32,                     // 注册优先级（#[route_codegen::priority(n)]）
+    signature: HandlerSignature,       // 文档注释和提取器参数，用于生成 OpenAPI
+    scope_anchor: Option<ScopeAnchor>, // 所在模块或上级模块声明的 scope（#[route_scope]）
+    cfgs: Vec<String>,                 // 所在模块和函数上的 #[cfg] 条件，复制到生成的注册代码上
+}
+
+impl RouteFunction {
+    /// 源码位置（文件:行号），用于诊断信息
+    fn location(&self) -> String {
+        format!("{}:{}", self.source_file.display(), self.line)
+    }
+
+    /// 展开为 (HTTP 方法, 路由路径) 列表
+    fn endpoints(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.routes.iter().flat_map(|route| {
+            route
+                .methods
+                .iter()
+                .map(move |method| (method.as_str(), route.path.as_str()))
+        })
+    }
+
+    /// 单条路由生效的条件：handler 的条件加上路由属性外层 `cfg_attr` 的条件
+    fn route_cfgs(&self, route: &RouteAttribute) -> Vec<String> {
+        let mut cfgs = self.cfgs.clone();
+        for cfg in &route.cfgs {
+            if !cfgs.contains(cfg) {
+                cfgs.push(cfg.clone());
+            }
+        }
+        cfgs
+    }
+}
+
+/// 提取函数上的路由属性（如 #[get(...)]、#[route(...)]、#[routes]）
+///
+/// 不是 handler 或标注了 `#[route_codegen::skip]` 的函数返回 `Ok(None)`，属性无法解析时返回错误。
+fn extract_route_info(fn_item: &ItemFn) -> syn::Result<Option<RouteFunction>> {
+    if is_skipped(&fn_item.attrs) {
+        return Ok(None);
+    }
+    let (routes, service_cfgs) = parse_route_attributes(&fn_item.attrs)?;
+    if routes.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(RouteFunction {
+        name: fn_item.sig.ident.to_string(),
+        routes,
+        priority: parse_priority(&fn_item.attrs)?,
+        module_prefix: String::new(), // 初始化新增字段
+        crate_root: String::new(),
+        source_file: PathBuf::new(),
+        line: fn_item.sig.ident.span().start().line,
+        signature: parse_handler_signature(fn_item),
+        scope_anchor: None,
+        cfgs: [parse_cfgs(&fn_item.attrs), service_cfgs].concat(),
+    }))
+}
+
+/// 构建模块前缀字符串
+fn build_module_prefix(current_module: &[String]) -> Cow<'_, str> {
+    let mut result = String::new();
+    let mut first = true;
+
+    for s in current_module {
+        match s.as_str() {
+            "crate" => continue,
+            _ => {
+                if !first {
+                    result.push_str("::");
+                }
+                result.push_str(s);
+                first = false;
+            }
+        }
+    }
+
+    Cow::Owned(result)
+}
+/// 将路径标准化为 Unix 风格（使用 '/' 分隔符）
+fn normalize_path<P: AsRef<Path>>(path: &P) -> Cow<'_, str> {
+    let path_str = path.as_ref().to_str().unwrap_or_default();
+    if path_str.contains('\\') {
+        Cow::Owned(path_str.replace("\\", "/"))
+    } else {
+        Cow::Borrowed(path_str)
+    }
+}
@@ -0,0 +1,201 @@
+use crate::codegen_attr::cfg_attribute;
+use crate::configure_builder::{collect_endpoints, ModuleGroup};
+use crate::workspace::{normalize, workspace_root};
+use crate::RouteFunction;
+use quote::quote;
+use route_codegen_runtime::path_params;
+use std::path::Path;
+
+/// 路由表中的一条路由，字段与 `route_codegen_runtime::RouteInfo` 一一对应
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RouteEntry {
+    pub method: String,           // HTTP 方法（如 GET）
+    pub path: String,             // 完整路径（scope + 路由路径）
+    pub handler: String,          // handler 的完整路径（如 crate::handler::nation::hello）
+    pub crate_name: String,       // handler 所在 crate
+    pub module: String,           // handler 在所在 crate 内的模块路径
+    pub file: String,             // 定义 handler 的源码文件（相对路径，见 `relative_source_file`）
+    pub line: u32,                // handler 所在行号
+    pub path_params: Vec<String>, // 路径参数名（如 ["id"]）
+}
+
+/// 按注册顺序展开路由表；`local_crate_name` 为被扫描 crate 自身的名称
+pub fn route_entries(
+    groups: &[ModuleGroup],
+    manifest_dir: &str,
+    local_crate_name: &str,
+) -> Vec<RouteEntry> {
+    let workspace_dir = workspace_root(Path::new(manifest_dir));
+    collect_endpoints(groups)
+        .into_iter()
+        .map(|endpoint| {
+            let function = endpoint.function;
+            let (crate_name, module) = owning_crate_and_module(function);
+            let file = relative_source_file(
+                &function.source_file,
+                manifest_dir,
+                workspace_dir.as_deref(),
+                crate_name.as_deref().unwrap_or(local_crate_name),
+            );
+            RouteEntry {
+                method: endpoint.method.to_string(),
+                path_params: path_params(&endpoint.path),
+                path: endpoint.path,
+                handler: handler_path(function),
+                crate_name: crate_name.unwrap_or_else(|| local_crate_name.to_string()),
+                module,
+                file,
+                line: function.line as u32,
+            }
+        })
+        .collect()
+}
+
+/// 生成 `pub static ROUTES: &[RouteInfo]`，按注册顺序描述所有路由
+///
+/// 源码文件使用相对路径（见 `relative_source_file`），保证生成结果与构建机器无关。
+/// 带 `#[cfg]` 的 handler 对应的条目带有相同的条件。
+/// 本地 handler 的 crate 名称使用 `env!("CARGO_CRATE_NAME")`，在编译生成代码时确定，
+/// 这样在 build.rs 中生成时也能得到正确的名称。
+pub fn build_route_manifest(
+    groups: &[ModuleGroup],
+    manifest_dir: &str,
+) -> proc_macro2::TokenStream {
+    let cfgs = collect_endpoints(groups)
+        .into_iter()
+        .map(|endpoint| cfg_attribute(&endpoint.function.route_cfgs(endpoint.route)));
+    let entries = route_entries(groups, manifest_dir, "")
+        .into_iter()
+        .zip(cfgs)
+        .map(|(entry, cfg_attr)| {
+            let RouteEntry {
+                method,
+                path,
+                handler,
+                crate_name,
+                module,
+                file,
+                line,
+                path_params,
+            } = entry;
+            // 空名称表示被扫描 crate 自身的 handler
+            let crate_name = if crate_name.is_empty() {
+                quote! { env!("CARGO_CRATE_NAME") }
+            } else {
+                quote! { #crate_name }
+            };
+
+            quote! {
+                #cfg_attr
+                route_codegen_runtime::RouteInfo {
+                    method: #method,
+                    path: #path,
+                    handler: #handler,
+                    crate_name: #crate_name,
+                    module: #module,
+                    file: #file,
+                    line: #line,
+                    path_params: &[#(#path_params),*],
+                }
+            }
+        });
+
+    quote! {
+        /// 按注册顺序排列的全部路由
+        #[allow(dead_code)]
+        pub static ROUTES: &[route_codegen_runtime::RouteInfo] = &[
+            #(#entries),*
+        ];
+    }
+}
+
+/// 路由表中的源码文件路径，与构建机器上的目录无关：
+///
+/// - 被扫描 crate 目录下的文件相对该目录（如 src/handler/user.rs）；
+/// - workspace 中其他位置的文件（如同级的 path 依赖）相对 workspace 根，与静态注册中 `file!()` 的形式一致；
+/// - workspace 之外的依赖以 crate 名称开头，加上相对该 crate 目录的路径（如 api_tool/src/api.rs）。
+fn relative_source_file(
+    source_file: &Path,
+    manifest_dir: &str,
+    workspace_dir: Option<&Path>,
+    crate_name: &str,
+) -> String {
+    if let Ok(relative) = source_file.strip_prefix(manifest_dir) {
+        return crate::normalize_path(&relative).into_owned();
+    }
+    let source_file = normalize(source_file);
+    if let Some(relative) = workspace_dir.and_then(|dir| source_file.strip_prefix(dir).ok()) {
+        return crate::normalize_path(&relative).into_owned();
+    }
+    // 源码文件上方最近的 Cargo.toml 所在目录即依赖的 crate 目录
+    let crate_dir = source_file
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.join("Cargo.toml").is_file());
+    match crate_dir.and_then(|dir| source_file.strip_prefix(dir).ok()) {
+        Some(relative) => format!("{}/{}", crate_name, crate::normalize_path(&relative)),
+        None => crate::normalize_path(&source_file).into_owned(),
+    }
+}
+
+/// handler 所属的 crate 名称（被扫描 crate 自身的 handler 为 None），以及它在该 crate 内的模块路径
+fn owning_crate_and_module(function: &RouteFunction) -> (Option<String>, String) {
+    if function.crate_root == "crate" {
+        return (None, function.module_prefix.clone());
+    }
+
+    let module = function
+        .module_prefix
+        .strip_prefix(&function.crate_root)
+        .unwrap_or(&function.module_prefix)
+        .trim_start_matches("::")
+        .to_string();
+    (Some(function.crate_root.clone()), module)
+}
+
+/// 从调用宏的 crate 看到的 handler 路径（如 crate::handler::nation::hello）
+fn handler_path(function: &RouteFunction) -> String {
+    let mut segments = Vec::new();
+    if function.crate_root == "crate" {
+        segments.push("crate");
+    }
+    if !function.module_prefix.is_empty() {
+        segments.push(function.module_prefix.as_str());
+    }
+    segments.push(function.name.as_str());
+    segments.join("::")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    #[test]
+    fn source_files_are_relative() {
+        let dir = TestDir::new("manifest_files");
+        dir.write(
+            "ws/Cargo.toml",
+            "[workspace]\nmembers = [\"app\", \"lib\"]\n",
+        );
+        dir.write("ws/app/Cargo.toml", "[package]\nname = \"app\"\n");
+        let local = dir.write("ws/app/src/main.rs", "");
+        let sibling = dir.write("ws/lib/src/user.rs", "");
+        dir.write("ext/Cargo.toml", "[package]\nname = \"ext\"\n");
+        let external = dir.write("ext/src/api/mod.rs", "");
+
+        let manifest_dir = dir.path().join("ws/app");
+        let workspace_dir = workspace_root(&manifest_dir);
+        let file = |source_file: &Path| {
+            relative_source_file(
+                source_file,
+                manifest_dir.to_str().unwrap(),
+                workspace_dir.as_deref(),
+                "my_ext",
+            )
+        };
+        assert_eq!(file(&local), "src/main.rs");
+        assert_eq!(file(&sibling), "lib/src/user.rs");
+        assert_eq!(file(&external), "my_ext/src/api/mod.rs");
+    }
+}
@@ -0,0 +1,53 @@
+use crate::codegen_attr::is_codegen_attribute;
+use crate::configure_builder::CodegenOptions;
+use crate::module_fn::ModuleFn;
+use quote::quote;
+use serde::{Deserialize, Serialize};
+use syn::ItemFn;
+
+/// 模块中标注了 `#[route_middleware]` 的函数，作用于该模块及其子模块的 scope
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteMiddleware {
+    pub function: ModuleFn, // 中间件函数及其所在模块
+    pub from_fn: bool,      // 带参数的中间件函数，通过 middleware::from_fn 包装
+}
+
+/// 读取函数上的 #[route_middleware]，未标注时返回 None
+///
+/// 不带参数的函数返回中间件本身（如 `impl Transform` 或 `from_fn(..)`），调用后传给 `wrap`；
+/// 带参数的函数是 `async fn(ServiceRequest, Next<B>)` 形式的中间件，使用 `from_fn` 包装。
+pub fn parse_route_middleware(fn_item: &ItemFn) -> Option<RouteMiddleware> {
+    if !fn_item
+        .attrs
+        .iter()
+        .any(|attr| is_codegen_attribute(attr, "route_middleware"))
+    {
+        return None;
+    }
+
+    Some(RouteMiddleware {
+        function: ModuleFn::new(fn_item),
+        from_fn: !fn_item.sig.inputs.is_empty(),
+    })
+}
+
+/// 生成 `.wrap(..)` 调用链
+///
+/// actix 中最后一次 `wrap` 的中间件最先执行，这里逆序生成，
+/// 让上级模块的中间件在外层，同一模块中先声明的中间件先执行。
+pub fn wrap_tokens(
+    chain: &[RouteMiddleware],
+    options: &CodegenOptions,
+) -> proc_macro2::TokenStream {
+    let wraps = chain.iter().rev().map(|middleware| {
+        let path = middleware.function.path(options);
+        if middleware.from_fn {
+            quote! { .wrap(actix_web::middleware::from_fn(#path)) }
+        } else {
+            quote! { .wrap(#path()) }
+        }
+    });
+    quote! {
+        #(#wraps)*
+    }
+}
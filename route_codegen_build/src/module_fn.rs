@@ -0,0 +1,98 @@
+use crate::codegen_attr::{parse_cfgs, ScopeAnchor};
+use crate::configure_builder::{item_path, module_segments, CodegenOptions};
+use crate::middleware::RouteMiddleware;
+use crate::provider::RouteProvider;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use syn::{punctuated::Punctuated, ItemFn, PathSegment, Token};
+
+/// 模块中带 route_codegen 标记属性的函数（中间件、provider、configure hook）
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleFn {
+    pub name: String,                      // 函数名称
+    pub module_prefix: String,             // 所在模块路径（与 RouteFunction::module_prefix 相同）
+    pub crate_root: String,                // 所属 crate 的路径根（调用宏的 crate 为 "crate"）
+    pub source_file: PathBuf,              // 定义该函数的源码文件
+    pub line: usize,                       // 函数所在行号
+    pub scope_anchor: Option<ScopeAnchor>, // 所在模块或上级模块声明的 scope（#[route_scope]）
+    pub cfgs: Vec<String>,                 // 所在模块和函数上的 #[cfg] 条件
+    #[serde(default)]
+    pub paths: Vec<String>, // configure hook 中以字符串字面量注册的路径，用于检查是否被 handler 遮蔽
+}
+
+impl ModuleFn {
+    /// 模块位置由解析模块树时填写
+    pub fn new(fn_item: &ItemFn) -> Self {
+        Self {
+            name: fn_item.sig.ident.to_string(),
+            module_prefix: String::new(),
+            crate_root: String::new(),
+            source_file: PathBuf::new(),
+            line: fn_item.sig.ident.span().start().line,
+            scope_anchor: None,
+            cfgs: parse_cfgs(&fn_item.attrs),
+            paths: Vec::new(),
+        }
+    }
+
+    /// 源码位置（文件:行号），用于诊断信息
+    pub fn location(&self) -> String {
+        format!("{}:{}", self.source_file.display(), self.line)
+    }
+
+    /// 从生成代码的位置引用该函数的路径
+    pub fn path(&self, options: &CodegenOptions) -> Punctuated<PathSegment, Token![::]> {
+        item_path(&self.crate_root, &self.module_prefix, &self.name, options)
+    }
+
+    /// 所在模块的路径段
+    pub fn module_path(&self) -> Vec<String> {
+        module_segments(&self.module_prefix)
+    }
+}
+
+/// 作用于某个模块的函数：同一 crate 中声明在该模块或其上级模块中的函数，
+/// 上级模块的在前，同一模块中保持声明顺序
+pub fn module_chain<T: Clone>(
+    crate_root: &str,
+    module_path: &[String],
+    items: &[T],
+    function: impl Fn(&T) -> &ModuleFn,
+) -> Vec<T> {
+    let mut chain: Vec<(usize, &T)> = items
+        .iter()
+        .filter_map(|item| {
+            let item_fn = function(item);
+            let segments = item_fn.module_path();
+            (item_fn.crate_root == crate_root && module_path.starts_with(&segments))
+                .then_some((segments.len(), item))
+        })
+        .collect();
+    chain.sort_by(|a, b| {
+        let (a_fn, b_fn) = (function(a.1), function(b.1));
+        a.0.cmp(&b.0)
+            .then_with(|| a_fn.source_file.cmp(&b_fn.source_file))
+            .then_with(|| a_fn.line.cmp(&b_fn.line))
+    });
+    chain.into_iter().map(|(_, item)| item.clone()).collect()
+}
+
+/// 按模块和声明位置排序，保证生成代码稳定
+pub fn sort_module_fns<T>(items: &mut [T], function: impl Fn(&T) -> &ModuleFn) {
+    items.sort_by(|a, b| {
+        let (a, b) = (function(a), function(b));
+        a.module_prefix
+            .cmp(&b.module_prefix)
+            .then_with(|| a.crate_root.cmp(&b.crate_root))
+            .then_with(|| a.source_file.cmp(&b.source_file))
+            .then_with(|| a.line.cmp(&b.line))
+    });
+}
+
+/// 扫描到的所有模块级函数
+#[derive(Clone, Debug, Default)]
+pub struct ModuleFns {
+    pub middlewares: Vec<RouteMiddleware>, // #[route_middleware]
+    pub providers: Vec<RouteProvider>,     // #[route_provider]
+    pub hooks: Vec<ModuleFn>,              // #[configure_hook]
+}
@@ -0,0 +1,397 @@
+use crate::codegen_attr::{RouteScope, ScopeAnchor};
+use crate::configure_builder::module_segments;
+use crate::dependency_tracker::DependencyTracker;
+use crate::diagnostics::Diagnostics;
+use crate::file_summary::{ItemSummary, ModuleSummary, ScopeDeclaration};
+use crate::module_fn::{sort_module_fns, ModuleFn, ModuleFns};
+use crate::openapi::SchemaStruct;
+use crate::scan_cache::ScanCache;
+use crate::tools::collect_rs_files;
+use crate::type_path::ModuleNames;
+use crate::{build_module_prefix, RouteFunction};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 按照 rustc 的规则解析模块树：从 main.rs / lib.rs 出发，
+/// 跟随 `mod x;` 声明、`#[path = "..."]` 覆盖和内联模块，只收集可达模块中的路由函数。
+pub struct ModuleResolver<'a> {
+    tracker: &'a DependencyTracker,
+    cache: &'a ScanCache,
+    diagnostics: &'a Diagnostics,
+    visited: Mutex<BTreeSet<PathBuf>>,
+    schemas: Mutex<Vec<SchemaStruct>>, // 可达模块中的结构体定义
+    module_fns: Mutex<ModuleFns>,      // 可达模块中的中间件、provider 等模块级函数
+    modules: Mutex<BTreeMap<String, ModuleNames>>, // 可达模块的完整路径 -> 模块中的名称
+}
+
+/// 解析某个模块内条目时的上下文
+#[derive(Clone)]
+struct ModuleContext {
+    module_path: Vec<String>,          // 模块路径（如 crate::handler::agency）
+    dir: PathBuf,                      // 子模块文件所在目录
+    path_attr_base: PathBuf,           // #[path] 属性的相对基准目录
+    scope_anchor: Option<ScopeAnchor>, // 最近一次声明的 scope（#[route_scope] / module_scope!）
+    cfgs: Vec<String>,                 // 模块及其上级模块上的 #[cfg] 条件
+}
+
+impl ModuleContext {
+    /// 记录模块上的 #[cfg] 条件，模块中的 handler 只在条件成立时注册
+    fn with_cfgs(mut self, cfgs: &[String]) -> Self {
+        self.cfgs.extend_from_slice(cfgs);
+        self
+    }
+
+    /// 应用模块的 scope 声明，声明后该模块及其子模块的前缀从声明的 scope 开始推导
+    fn with_route_scope(
+        mut self,
+        scope: &ScopeDeclaration,
+        path: &Path,
+        diagnostics: &Diagnostics,
+    ) -> Self {
+        match scope {
+            Ok(Some(scope)) => {
+                let scope = match scope {
+                    RouteScope::Path(scope) => scope.clone(),
+                    RouteScope::Flat => String::new(),
+                };
+                self.scope_anchor = Some(ScopeAnchor {
+                    scope,
+                    depth: module_segments(&build_module_prefix(&self.module_path)).len(),
+                });
+            }
+            Ok(None) => {}
+            Err(e) => diagnostics.error(format!(
+                "{}:{}: invalid route scope for module `{}`: {}",
+                path.display(),
+                e.line,
+                self.module_path.join("::"),
+                e.message
+            )),
+        }
+        self
+    }
+}
+
+impl<'a> ModuleResolver<'a> {
+    pub fn new(
+        tracker: &'a DependencyTracker,
+        cache: &'a ScanCache,
+        diagnostics: &'a Diagnostics,
+    ) -> Self {
+        Self {
+            tracker,
+            cache,
+            diagnostics,
+            visited: Mutex::new(BTreeSet::new()),
+            schemas: Mutex::new(Vec::new()),
+            module_fns: Mutex::new(ModuleFns::default()),
+            modules: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// 从 crate 根文件开始解析整个模块树，`cfgs` 为启用该 crate 所需的条件（如 optional 依赖的 feature）
+    pub fn resolve_crate(
+        &self,
+        root_file: &Path,
+        crate_root: &str,
+        cfgs: &[String],
+    ) -> Vec<RouteFunction> {
+        let dir = root_file.parent().unwrap_or(Path::new(".")).to_path_buf();
+        let context = ModuleContext {
+            module_path: vec![crate_root.to_string()],
+            dir: dir.clone(),
+            path_attr_base: dir,
+            scope_anchor: None,
+            cfgs: cfgs.to_vec(),
+        };
+        self.resolve_file(root_file, context)
+    }
+
+    /// 所有已解析的模块文件
+    pub fn visited_files(&self) -> BTreeSet<PathBuf> {
+        self.visited.lock().unwrap().clone()
+    }
+
+    /// 所有可达模块中的名称，用于解析类型路径
+    pub fn module_names(&self) -> BTreeMap<String, ModuleNames> {
+        self.modules.lock().unwrap().clone()
+    }
+
+    /// 所有可达模块中带命名字段的结构体（按名称排序）
+    pub fn schemas(&self) -> Vec<SchemaStruct> {
+        let mut schemas = self.schemas.lock().unwrap().clone();
+        schemas.sort_by_key(SchemaStruct::path);
+        schemas
+    }
+
+    /// 所有可达模块中的模块级函数（按模块和声明位置排序）
+    pub fn module_fns(&self) -> ModuleFns {
+        let mut module_fns = self.module_fns.lock().unwrap().clone();
+        sort_module_fns(&mut module_fns.middlewares, |m| &m.function);
+        sort_module_fns(&mut module_fns.providers, |p| &p.function);
+        sort_module_fns(&mut module_fns.hooks, |h| h);
+        module_fns
+    }
+
+    /// 中间件和 provider 在生成的调用链中无条件引用，带 #[cfg] 时在条件不成立的构建中无法编译
+    fn warn_conditional(&self, kind: &str, function: &ModuleFn) {
+        if !function.cfgs.is_empty() {
+            self.diagnostics.warn(format!(
+                "{}: {} `{}` is behind #[cfg({})], but the generated code references it unconditionally",
+                function.location(),
+                kind,
+                function.name,
+                function.cfgs.join(", ")
+            ));
+        }
+    }
+
+    /// 解析单个模块文件
+    fn resolve_file(&self, path: &Path, context: ModuleContext) -> Vec<RouteFunction> {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if !self.visited.lock().unwrap().insert(canonical) {
+            return Vec::new();
+        }
+
+        // 先登记依赖，即使文件暂时无法解析，修复后也能触发重新编译
+        self.tracker.track(path);
+
+        self.diagnostics
+            .info(format!("🔍 Processing file: {:?}", path));
+        self.diagnostics.info(format!(
+            "📦 Module path: {}",
+            context.module_path.join("::")
+        ));
+
+        let summary = match self.cache.summary(path) {
+            Ok(summary) => summary,
+            Err(e) => {
+                self.diagnostics.problem(format!(
+                    "{}; handlers in module `{}` are not registered",
+                    e,
+                    context.module_path.join("::")
+                ));
+                return Vec::new();
+            }
+        };
+
+        self.modules
+            .lock()
+            .unwrap()
+            .insert(context.module_path.join("::"), summary.names.clone());
+
+        // 模块文件顶部的 #![route_scope(..)] 或 module_scope!(..)
+        let context = context
+            .with_route_scope(&summary.scope, path, self.diagnostics)
+            .with_cfgs(&summary.cfgs);
+        self.resolve_items(&summary.items, path, &context)
+    }
+
+    /// 解析模块中的条目：收集路由函数，递归处理内联模块和文件模块
+    fn resolve_items(
+        &self,
+        items: &[ItemSummary],
+        path: &Path,
+        context: &ModuleContext,
+    ) -> Vec<RouteFunction> {
+        let mut result = Vec::new();
+        let mut file_modules = Vec::new();
+
+        for item in items {
+            match item {
+                ItemSummary::Middleware(middleware) => {
+                    let mut middleware = middleware.clone();
+                    locate(&mut middleware.function, path, context);
+                    self.warn_conditional("#[route_middleware]", &middleware.function);
+                    self.diagnostics.info(format!(
+                        "🧩 Middleware `{}` (module: {:?})",
+                        middleware.function.name, middleware.function.module_prefix
+                    ));
+                    self.module_fns.lock().unwrap().middlewares.push(middleware);
+                }
+                ItemSummary::Provider(provider) => {
+                    let mut provider = provider.clone();
+                    locate(&mut provider.function, path, context);
+                    self.warn_conditional("#[route_provider]", &provider.function);
+                    self.diagnostics.info(format!(
+                        "🗃️ Provider `{}` for web::Data<{}> (module: {:?})",
+                        provider.function.name, provider.data_type, provider.function.module_prefix
+                    ));
+                    self.module_fns.lock().unwrap().providers.push(provider);
+                }
+                ItemSummary::Hook(hook) => {
+                    let mut hook = hook.clone();
+                    locate(&mut hook, path, context);
+                    self.diagnostics.info(format!(
+                        "🪝 Configure hook `{}` (module: {:?})",
+                        hook.name, hook.module_prefix
+                    ));
+                    self.module_fns.lock().unwrap().hooks.push(hook);
+                }
+                ItemSummary::Route(route_fn) => {
+                    let mut route_fn = route_fn.clone();
+                    route_fn.module_prefix = build_module_prefix(&context.module_path).to_string();
+                    route_fn.source_file = path.to_path_buf();
+                    route_fn.crate_root = context.module_path[0].clone();
+                    route_fn.scope_anchor = context.scope_anchor.clone();
+                    route_fn.cfgs = [context.cfgs.as_slice(), &route_fn.cfgs].concat();
+                    result.push(route_fn);
+                }
+                ItemSummary::Error(e) => {
+                    self.diagnostics
+                        .error(format!("{}:{}: {}", path.display(), e.line, e.message))
+                }
+                ItemSummary::Schema(schema) => {
+                    let mut schema = schema.clone();
+                    schema.module = context.module_path.clone();
+                    self.schemas.lock().unwrap().push(schema);
+                }
+                ItemSummary::Module(module) => {
+                    let mut module_path = context.module_path.clone();
+                    module_path.push(module.name.clone());
+                    self.diagnostics.info(format!(
+                        "📁 路由模块 '{}', stack: {:?}",
+                        module.name, module_path
+                    ));
+
+                    match &module.items {
+                        Some(inner_items) => {
+                            self.modules
+                                .lock()
+                                .unwrap()
+                                .insert(module_path.join("::"), module.names.clone());
+                            let dir = match &module.path_attr {
+                                Some(p) => context.path_attr_base.join(p),
+                                None => context.dir.join(&module.name),
+                            };
+                            let inner_context = ModuleContext {
+                                module_path,
+                                dir: dir.clone(),
+                                path_attr_base: dir,
+                                scope_anchor: context.scope_anchor.clone(),
+                                cfgs: context.cfgs.clone(),
+                            }
+                            .with_route_scope(&module.scope, path, self.diagnostics)
+                            .with_cfgs(&module.cfgs);
+                            result.extend(self.resolve_items(inner_items, path, &inner_context));
+                        }
+                        None => match resolve_module_file(module, module_path, context) {
+                            Ok((child_path, child_context)) => {
+                                // `mod x;` 上的 scope 声明会被文件内的声明覆盖
+                                let child_context = child_context
+                                    .with_route_scope(&module.scope, path, self.diagnostics)
+                                    .with_cfgs(&module.cfgs);
+                                file_modules.push((child_path, child_context))
+                            }
+                            Err(e) => self.diagnostics.problem(format!(
+                                "{}:{}: {}",
+                                path.display(),
+                                module.line,
+                                e
+                            )),
+                        },
+                    }
+                }
+            }
+        }
+
+        let children: Vec<RouteFunction> = file_modules
+            .into_par_iter()
+            .flat_map(|(child_path, child_context)| self.resolve_file(&child_path, child_context))
+            .collect();
+        result.extend(children);
+        result
+    }
+}
+
+/// 填写模块级函数所在的模块和文件
+fn locate(function: &mut ModuleFn, path: &Path, context: &ModuleContext) {
+    function.module_prefix = build_module_prefix(&context.module_path).to_string();
+    function.source_file = path.to_path_buf();
+    function.crate_root = context.module_path[0].clone();
+    function.scope_anchor = context.scope_anchor.clone();
+    function.cfgs = [context.cfgs.as_slice(), &function.cfgs].concat();
+}
+
+/// 计算 `mod x;` 声明对应的文件以及它的子模块目录
+fn resolve_module_file(
+    module: &ModuleSummary,
+    module_path: Vec<String>,
+    context: &ModuleContext,
+) -> Result<(PathBuf, ModuleContext), String> {
+    let not_found = |file: &Path| {
+        format!(
+            "module file not found for `{}`: {}",
+            module_path.join("::"),
+            file.display()
+        )
+    };
+
+    let (file, dir) = if let Some(p) = &module.path_attr {
+        // 通过 #[path] 加载的文件与 mod.rs 一样拥有自己所在的目录
+        let file = context.path_attr_base.join(p);
+        let dir = file.parent().ok_or_else(|| not_found(&file))?.to_path_buf();
+        (file, dir)
+    } else {
+        let flat = context.dir.join(format!("{}.rs", module.name));
+        let nested = context.dir.join(&module.name).join("mod.rs");
+        if flat.is_file() {
+            (flat, context.dir.join(&module.name))
+        } else if nested.is_file() {
+            (nested, context.dir.join(&module.name))
+        } else {
+            return Err(not_found(&flat));
+        }
+    };
+
+    if !file.is_file() {
+        return Err(not_found(&file));
+    }
+
+    // 非 mod-rs 文件（如 agency.rs）的顶层 #[path] 相对于文件所在目录解析
+    let path_attr_base = file.parent().ok_or_else(|| not_found(&file))?.to_path_buf();
+    Ok((
+        file,
+        ModuleContext {
+            module_path,
+            dir,
+            path_attr_base,
+            scope_anchor: context.scope_anchor.clone(),
+            cfgs: context.cfgs.clone(),
+        },
+    ))
+}
+
+/// 报告 src 目录下包含路由属性、但没有被模块树引用的孤立文件
+///
+/// 结果取决于 src 目录中有哪些文件，因此 src 目录被登记为依赖。
+pub fn report_orphan_route_files(
+    src_dir: &Path,
+    visited: &BTreeSet<PathBuf>,
+    tracker: &DependencyTracker,
+    cache: &ScanCache,
+    diagnostics: &Diagnostics,
+) {
+    tracker.track_dir(src_dir);
+    // src/bin 下是独立的编译目标，不属于这个模块树
+    let files = collect_rs_files(src_dir, &[src_dir.join("bin")]);
+
+    let orphans: Vec<PathBuf> = files
+        .into_par_iter()
+        .filter(|file| {
+            let canonical = file.canonicalize().unwrap_or_else(|_| file.clone());
+            !visited.contains(&canonical)
+                && cache
+                    .summary(file)
+                    .is_ok_and(|summary| summary.has_routes())
+        })
+        .collect();
+    for file in orphans {
+        diagnostics.warn(format!(
+            "{} contains route handlers but is not reachable from the crate's module tree; add a `mod` declaration for it",
+            file.display()
+        ));
+    }
+}
@@ -0,0 +1,764 @@
+use crate::codegen_attr::cfg_attribute;
+use crate::configure_builder::{collect_endpoints, ModuleGroup};
+use crate::provider::{data_type, written_type};
+use crate::type_path::{module_path, ModuleNames, TypeResolver};
+use crate::RouteFunction;
+use quote::quote;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use syn::ext::IdentExt;
+use syn::{
+    Attribute, FnArg, GenericArgument, ItemFn, ItemStruct, LitStr, PathArguments, Token, Type,
+};
+
+/// 从 handler 签名中提取的文档信息
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HandlerSignature {
+    pub docs: Vec<String>,          // `///` 文档注释（逐行）
+    pub extractors: Vec<Extractor>, // 参数中的 actix 提取器
+    pub data: Vec<String>, // 参数中 web::Data<T> 的类型，源码中书写的形式（用于检查 provider）
+}
+
+/// actix 提取器及其内部类型
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Extractor {
+    Path(TypeShape),  // web::Path<T>
+    Query(TypeShape), // web::Query<T>
+    Json(TypeShape),  // web::Json<T>
+    Form(TypeShape),  // web::Form<T>
+}
+
+/// 生成 schema 所需的类型结构
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum TypeShape {
+    Primitive(String, Option<String>), // OpenAPI 类型和 format
+    Unsigned(String),                  // 无符号整数的 format（int32 / int64），schema 带 minimum: 0
+    Optional(Box<TypeShape>),          // Option<T>
+    Array(Box<TypeShape>),             // Vec<T> / [T]
+    Tuple(Vec<TypeShape>),             // (A, B)
+    Named(String),                     // 自定义结构体，源码中书写的路径（如 models::User）
+    Unknown,                           // 无法识别的类型
+}
+
+/// 扫描到的结构体定义，用于展开 Path/Query 参数和生成 components.schemas
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SchemaStruct {
+    pub name: String,
+    pub module: Vec<String>, // 所在模块的完整路径（如 crate::models），解析模块树时填写
+    pub docs: Vec<String>,
+    pub fields: Vec<(String, TypeShape, Vec<String>)>, // 序列化名称（应用 serde rename）、类型、文档
+}
+
+impl SchemaStruct {
+    /// 完整路径（如 crate::models::User）
+    pub fn path(&self) -> String {
+        [self.module.as_slice(), std::slice::from_ref(&self.name)]
+            .concat()
+            .join("::")
+    }
+}
+
+/// 解析 handler 的文档注释和提取器参数
+pub fn parse_handler_signature(fn_item: &ItemFn) -> HandlerSignature {
+    let extractors = fn_item
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat_type) => parse_extractor(&pat_type.ty),
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+
+    let data = fn_item
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat_type) => data_type(&pat_type.ty).map(written_type),
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+
+    HandlerSignature {
+        docs: doc_lines(&fn_item.attrs),
+        extractors,
+        data,
+    }
+}
+
+/// 解析带命名字段的结构体
+pub fn parse_schema_struct(item: &ItemStruct) -> Option<SchemaStruct> {
+    let syn::Fields::Named(fields) = &item.fields else {
+        return None;
+    };
+
+    let rename_all = serde_option(&item.attrs, "rename_all");
+    let fields = fields
+        .named
+        .iter()
+        .filter_map(|field| {
+            let ident = field.ident.as_ref()?.unraw().to_string();
+            let name = serde_option(&field.attrs, "rename")
+                .unwrap_or_else(|| rename_field(&ident, rename_all.as_deref()));
+            Some((name, type_shape(&field.ty), doc_lines(&field.attrs)))
+        })
+        .collect();
+
+    Some(SchemaStruct {
+        name: item.ident.unraw().to_string(),
+        module: Vec::new(),
+        docs: doc_lines(&item.attrs),
+        fields,
+    })
+}
+
+/// 读取 `#[serde(key = "..")]`，`key(serialize = "..", deserialize = "..")` 取 deserialize
+/// （schema 描述的是请求中的 Path/Query/Json/Form 参数）
+fn serde_option(attrs: &[Attribute], key: &str) -> Option<String> {
+    let mut value = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident(key) && meta.input.peek(Token![=]) {
+                value = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident(key) {
+                meta.parse_nested_meta(|inner| {
+                    let lit = inner.value()?.parse::<LitStr>()?;
+                    if inner.path.is_ident("deserialize") {
+                        value = Some(lit.value());
+                    }
+                    Ok(())
+                })?;
+            } else if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let _content;
+                syn::parenthesized!(_content in meta.input);
+            }
+            Ok(())
+        });
+    }
+    value
+}
+
+/// 按 `#[serde(rename_all = "..")]` 转换字段名（字段名本身是 snake_case）
+fn rename_field(field: &str, rename_all: Option<&str>) -> String {
+    let pascal = || {
+        field
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect::<String>()
+    };
+    match rename_all {
+        Some("lowercase") => field.to_ascii_lowercase(),
+        Some("UPPERCASE" | "SCREAMING_SNAKE_CASE") => field.to_ascii_uppercase(),
+        Some("PascalCase") => pascal(),
+        Some("camelCase") => {
+            let pascal = pascal();
+            let mut chars = pascal.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        }
+        Some("kebab-case") => field.replace('_', "-"),
+        Some("SCREAMING-KEBAB-CASE") => field.to_ascii_uppercase().replace('_', "-"),
+        _ => field.to_string(),
+    }
+}
+
+/// 读取 `///` 文档注释
+fn doc_lines(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(s),
+                        ..
+                    }),
+                ..
+            }) => Some(s.value().trim().to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 识别 web::Path<T> / web::Query<T> / web::Json<T> / web::Form<T>
+fn parse_extractor(ty: &Type) -> Option<Extractor> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    let inner = first_generic_type(&segment.arguments)?;
+    let shape = type_shape(inner);
+
+    match segment.ident.to_string().as_str() {
+        "Path" => Some(Extractor::Path(shape)),
+        "Query" => Some(Extractor::Query(shape)),
+        "Json" => Some(Extractor::Json(shape)),
+        "Form" => Some(Extractor::Form(shape)),
+        _ => None,
+    }
+}
+
+fn first_generic_type(arguments: &PathArguments) -> Option<&Type> {
+    let PathArguments::AngleBracketed(args) = arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+/// 把 Rust 类型转换为 schema 类型结构
+fn type_shape(ty: &Type) -> TypeShape {
+    match ty {
+        Type::Reference(reference) => type_shape(&reference.elem),
+        Type::Paren(paren) => type_shape(&paren.elem),
+        Type::Slice(slice) => TypeShape::Array(Box::new(type_shape(&slice.elem))),
+        Type::Array(array) => TypeShape::Array(Box::new(type_shape(&array.elem))),
+        Type::Tuple(tuple) => TypeShape::Tuple(tuple.elems.iter().map(type_shape).collect()),
+        Type::Path(type_path) => {
+            let Some(segment) = type_path.path.segments.last() else {
+                return TypeShape::Unknown;
+            };
+            let ident = segment.ident.to_string();
+            let inner = first_generic_type(&segment.arguments);
+            match (ident.as_str(), inner) {
+                ("Option", Some(inner)) => TypeShape::Optional(Box::new(type_shape(inner))),
+                ("Vec" | "VecDeque" | "HashSet" | "BTreeSet", Some(inner)) => {
+                    TypeShape::Array(Box::new(type_shape(inner)))
+                }
+                ("Box" | "Arc" | "Rc" | "Cow", Some(inner)) => type_shape(inner),
+                _ => primitive_shape(&ident).unwrap_or_else(|| {
+                    let path: Vec<String> = type_path
+                        .path
+                        .segments
+                        .iter()
+                        .map(|segment| segment.ident.unraw().to_string())
+                        .collect();
+                    TypeShape::Named(path.join("::"))
+                }),
+            }
+        }
+        _ => TypeShape::Unknown,
+    }
+}
+
+fn primitive_shape(ident: &str) -> Option<TypeShape> {
+    // OpenAPI 的 int32 / int64 是有符号的，无符号整数使用能容纳它的格式并限制 minimum
+    match ident {
+        "u8" | "u16" => return Some(TypeShape::Unsigned("int32".to_string())),
+        "u32" | "u64" | "u128" | "usize" => return Some(TypeShape::Unsigned("int64".to_string())),
+        _ => {}
+    }
+    let (ty, format) = match ident {
+        "String" | "str" | "char" => ("string", None),
+        "i8" | "i16" | "i32" => ("integer", Some("int32")),
+        "i64" | "i128" | "isize" => ("integer", Some("int64")),
+        "f32" => ("number", Some("float")),
+        "f64" => ("number", Some("double")),
+        "bool" => ("boolean", None),
+        "Uuid" => ("string", Some("uuid")),
+        _ => return None,
+    };
+    Some(TypeShape::Primitive(
+        ty.to_string(),
+        format.map(|format| format.to_string()),
+    ))
+}
+
+/// 生成 `pub fn openapi_json() -> &'static str`，内容为 OpenAPI 3.1 文档
+///
+/// `schemas` 以结构体的完整路径为键，`modules` 用于把 handler 中书写的类型解析为完整路径。
+/// 每个 operation 带有与路由相同的 `#[cfg]` 条件，文档在第一次调用时由生效的 operation 拼接而成。
+pub fn build_openapi(
+    groups: &[ModuleGroup],
+    schemas: &BTreeMap<String, SchemaStruct>,
+    modules: &BTreeMap<String, ModuleNames>,
+    package: &PackageInfo,
+) -> proc_macro2::TokenStream {
+    let document = openapi_document(groups, schemas, modules, package);
+    let info = document.info.to_string();
+    let operations = document.operations.iter().map(|operation| {
+        let cfg_attr = cfg_attribute(&operation.cfgs);
+        let path = Value::from(operation.path.as_str()).to_string();
+        let method = &operation.method;
+        let json = operation.json.to_string();
+        let schemas = operation
+            .schemas
+            .iter()
+            .map(|name| Value::from(name.as_str()).to_string());
+        quote! {
+            #cfg_attr
+            operations.push(route_codegen_runtime::openapi::Operation {
+                path: #path,
+                method: #method,
+                json: #json,
+                schemas: &[#(#schemas),*],
+            });
+        }
+    });
+    let schemas = document.schemas.iter().map(|(name, schema)| {
+        let name = Value::from(name.as_str()).to_string();
+        let schema = schema.to_string();
+        quote! { (#name, #schema) }
+    });
+
+    quote! {
+        /// 根据扫描到的 handler 生成的 OpenAPI 3.1 文档（JSON），只包含当前编译条件下注册的路由
+        #[allow(dead_code)]
+        pub fn openapi_json() -> &'static str {
+            static DOCUMENT: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+            DOCUMENT.get_or_init(|| {
+                #[allow(unused_mut)]
+                let mut operations = Vec::new();
+                #(#operations)*
+                route_codegen_runtime::openapi::document(#info, &operations, &[#(#schemas),*])
+            })
+        }
+    }
+}
+
+/// 被扫描 crate 的包信息，用作文档的 info（从 Cargo.toml 读取，不依赖当前进程的 CARGO_PKG_* 环境变量）
+pub struct PackageInfo {
+    pub name: String,    // package.name
+    pub version: String, // package.version
+}
+
+/// 编译时生成的文档内容，operation 在运行时按 cfg 条件拼接
+struct OpenApiDocument {
+    info: Value,
+    operations: Vec<OperationFragment>,
+    schemas: BTreeMap<String, Value>, // components.schemas
+}
+
+/// 一个 operation 及其生效条件
+struct OperationFragment {
+    path: String,         // OpenAPI 格式的路径
+    method: String,       // 小写的 HTTP 方法
+    json: Value,          // operation 对象
+    schemas: Vec<String>, // 直接或间接引用的 components.schemas
+    cfgs: Vec<String>,    // 路由的 #[cfg] 条件
+}
+
+fn openapi_document(
+    groups: &[ModuleGroup],
+    schemas: &BTreeMap<String, SchemaStruct>,
+    modules: &BTreeMap<String, ModuleNames>,
+    package: &PackageInfo,
+) -> OpenApiDocument {
+    let mut operations = Vec::new();
+    let mut components = Components {
+        schemas,
+        resolver: TypeResolver::new(modules),
+        referenced: BTreeMap::new(),
+    };
+    let mut operation_ids = BTreeMap::<String, usize>::new();
+
+    for endpoint in collect_endpoints(groups) {
+        // 同一个 handler 注册多个方法时，每个方法是独立的 operation，operationId 需要带上方法
+        let mut operation_id = operation_id(endpoint.function);
+        if endpoint.function.endpoints().count() > 1 {
+            operation_id = format!("{}_{}", operation_id, endpoint.method.to_lowercase());
+        }
+        let count = operation_ids.entry(operation_id.clone()).or_default();
+        *count += 1;
+        if *count > 1 {
+            operation_id = format!("{}_{}", operation_id, count);
+        }
+
+        let json = components.operation(endpoint.function, &endpoint.path, operation_id);
+        operations.push(OperationFragment {
+            path: openapi_path(&endpoint.path),
+            method: endpoint.method.to_lowercase(),
+            json,
+            schemas: Vec::new(),
+            cfgs: endpoint.function.route_cfgs(endpoint.route),
+        });
+    }
+
+    // 引用关系在所有 schema 生成之后才完整
+    for operation in &mut operations {
+        operation.schemas = referenced_schemas(&operation.json, &components.referenced);
+    }
+
+    OpenApiDocument {
+        info: json!({
+            "title": package.name,
+            "version": package.version,
+        }),
+        operations,
+        schemas: components.referenced,
+    }
+}
+
+/// operation 直接或通过其他 schema 间接引用的 components.schemas
+fn referenced_schemas(operation: &Value, schemas: &BTreeMap<String, Value>) -> Vec<String> {
+    let mut referenced = BTreeSet::new();
+    let mut pending = vec![operation];
+    while let Some(value) = pending.pop() {
+        match value {
+            Value::Object(map) => {
+                if let Some(name) = map
+                    .get("$ref")
+                    .and_then(Value::as_str)
+                    .and_then(|r| r.strip_prefix("#/components/schemas/"))
+                    && referenced.insert(name.to_string())
+                    && let Some(schema) = schemas.get(name)
+                {
+                    pending.push(schema);
+                }
+                pending.extend(map.values());
+            }
+            Value::Array(items) => pending.extend(items),
+            _ => {}
+        }
+    }
+    referenced.into_iter().collect()
+}
+
+fn operation_id(function: &RouteFunction) -> String {
+    if function.module_prefix.is_empty() {
+        function.name.clone()
+    } else {
+        format!(
+            "{}_{}",
+            function.module_prefix.replace("::", "_"),
+            function.name
+        )
+    }
+}
+
+/// actix 的 {tail:.*} 在 OpenAPI 中写作 {tail}
+fn openapi_path(path: &str) -> String {
+    let mut result = String::new();
+    let mut depth = 0;
+    let mut skipping = false;
+
+    for c in path.chars() {
+        match c {
+            '{' => {
+                depth += 1;
+                if depth == 1 {
+                    result.push(c);
+                }
+            }
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    result.push(c);
+                    skipping = false;
+                }
+            }
+            ':' if depth == 1 => skipping = true,
+            _ if depth > 1 || skipping => {}
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// 第一段文档（到第一个空行为止，多行合并为一行）作为 summary，其余作为 description
+fn summary_and_description(docs: &[String]) -> (Option<String>, Option<String>) {
+    let mut lines = docs.iter().skip_while(|line| line.is_empty()).peekable();
+    let mut summary = Vec::new();
+    while let Some(line) = lines.next_if(|line| !line.is_empty()) {
+        summary.push(line.as_str());
+    }
+    let summary = (!summary.is_empty()).then(|| summary.join(" "));
+    let description = lines
+        .skip_while(|line| line.is_empty())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\n");
+    let description = (!description.trim().is_empty()).then(|| description.trim().to_string());
+    (summary, description)
+}
+
+/// 生成 operation 时共享的状态：扫描到的结构体、类型路径解析器和已引用的 components.schemas
+struct Components<'a> {
+    schemas: &'a BTreeMap<String, SchemaStruct>, // 完整路径 -> 结构体定义
+    resolver: TypeResolver<'a>,
+    referenced: BTreeMap<String, Value>, // components.schemas 中的名称 -> schema
+}
+
+impl Components<'_> {
+    fn operation(
+        &mut self,
+        function: &RouteFunction,
+        full_path: &str,
+        operation_id: String,
+    ) -> Value {
+        let mut operation = Map::new();
+        operation.insert("operationId".into(), json!(operation_id));
+        if !function.module_prefix.is_empty() {
+            operation.insert("tags".into(), json!([function.module_prefix]));
+        }
+
+        let (summary, description) = summary_and_description(&function.signature.docs);
+        if let Some(summary) = summary {
+            operation.insert("summary".into(), json!(summary));
+        }
+        if let Some(description) = description {
+            operation.insert("description".into(), json!(description));
+        }
+
+        // 提取器中的类型按 handler 所在模块中的 use 声明解析
+        let module = module_path(&function.crate_root, &function.module_prefix);
+        let mut parameters = self.path_parameters(function, full_path, &module);
+        for extractor in &function.signature.extractors {
+            match extractor {
+                Extractor::Query(shape) => {
+                    parameters.extend(self.query_parameters(shape, &module));
+                }
+                Extractor::Json(shape) => {
+                    operation.insert(
+                        "requestBody".into(),
+                        self.request_body("application/json", shape, &module),
+                    );
+                }
+                Extractor::Form(shape) => {
+                    operation.insert(
+                        "requestBody".into(),
+                        self.request_body("application/x-www-form-urlencoded", shape, &module),
+                    );
+                }
+                Extractor::Path(_) => {}
+            }
+        }
+        if !parameters.is_empty() {
+            operation.insert("parameters".into(), Value::Array(parameters));
+        }
+
+        operation.insert(
+            "responses".into(),
+            json!({ "200": { "description": "OK" } }),
+        );
+        Value::Object(operation)
+    }
+
+    /// 查找模块 `module` 中书写的结构体：优先按解析出的完整路径查找，
+    /// 无法解析（如 glob 导入）时退回到名称唯一的结构体
+    fn lookup(&self, name: &str, module: &[String]) -> Option<&SchemaStruct> {
+        match self.resolver.item_path(name, module) {
+            Some(path) => self.schemas.get(&path),
+            None => {
+                let simple = name.rsplit("::").next().unwrap_or(name);
+                let mut candidates = self.schemas.values().filter(|schema| schema.name == simple);
+                match (candidates.next(), candidates.next()) {
+                    (Some(schema), None) => Some(schema),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// components.schemas 中的名称：结构体名唯一时直接使用，
+    /// 否则使用完整路径（如 admin.User、api_tool.models.User）
+    fn component_name(&self, schema: &SchemaStruct) -> String {
+        let same_name = self
+            .schemas
+            .values()
+            .filter(|other| other.name == schema.name)
+            .count();
+        if same_name > 1 {
+            let path = schema.path();
+            path.strip_prefix("crate::")
+                .unwrap_or(&path)
+                .replace("::", ".")
+        } else {
+            schema.name.clone()
+        }
+    }
+
+    /// 路径参数：名称来自路径模板，类型来自 web::Path<T>
+    fn path_parameters(
+        &mut self,
+        function: &RouteFunction,
+        full_path: &str,
+        module: &[String],
+    ) -> Vec<Value> {
+        let names = route_codegen_runtime::path_params(full_path);
+        let path_shape =
+            function
+                .signature
+                .extractors
+                .iter()
+                .find_map(|extractor| match extractor {
+                    Extractor::Path(shape) => Some(shape),
+                    _ => None,
+                });
+        let path_struct = match path_shape {
+            Some(TypeShape::Named(type_name)) => self.lookup(type_name, module),
+            _ => None,
+        };
+
+        // 路由自身路径中的参数与 Path<T> 对应，scope 中的参数排在前面
+        let route_params = function
+            .routes
+            .first()
+            .map(|route| route_codegen_runtime::path_params(&route.path))
+            .unwrap_or_default();
+        let offset = names.len().saturating_sub(route_params.len());
+
+        let shapes: Vec<Option<(TypeShape, Vec<String>)>> = names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| match path_shape? {
+                TypeShape::Tuple(items) => index
+                    .checked_sub(offset)
+                    .and_then(|i| items.get(i))
+                    .map(|shape| (shape.clone(), module.to_vec())),
+                TypeShape::Named(_) => {
+                    let schema = path_struct?;
+                    schema
+                        .fields
+                        .iter()
+                        .find(|(field, _, _)| field == name)
+                        .map(|(_, shape, _)| (shape.clone(), schema.module.clone()))
+                }
+                shape if index >= offset && route_params.len() == 1 => {
+                    Some((shape.clone(), module.to_vec()))
+                }
+                _ => None,
+            })
+            .collect();
+
+        names
+            .iter()
+            .zip(shapes)
+            .map(|(name, shape)| {
+                let schema = shape
+                    .map(|(shape, module)| self.schema_for(&shape, &module))
+                    .unwrap_or_else(|| json!({ "type": "string" }));
+                json!({
+                    "name": name,
+                    "in": "path",
+                    "required": true,
+                    "schema": schema,
+                })
+            })
+            .collect()
+    }
+
+    /// 查询参数：web::Query<T> 中结构体的每个字段
+    fn query_parameters(&mut self, shape: &TypeShape, module: &[String]) -> Vec<Value> {
+        let TypeShape::Named(type_name) = shape else {
+            return Vec::new();
+        };
+        let Some(schema) = self.lookup(type_name, module) else {
+            return Vec::new();
+        };
+        let schema = schema.clone();
+
+        schema
+            .fields
+            .iter()
+            .map(|(name, field_shape, docs)| {
+                let mut parameter = json!({
+                    "name": name,
+                    "in": "query",
+                    "required": !matches!(field_shape, TypeShape::Optional(_)),
+                    "schema": self.schema_for(field_shape, &schema.module),
+                });
+                if !docs.is_empty() {
+                    parameter["description"] = json!(docs.join("\n"));
+                }
+                parameter
+            })
+            .collect()
+    }
+
+    fn request_body(&mut self, content_type: &str, shape: &TypeShape, module: &[String]) -> Value {
+        json!({
+            "required": !matches!(shape, TypeShape::Optional(_)),
+            "content": {
+                content_type: { "schema": self.schema_for(shape, module) }
+            }
+        })
+    }
+
+    /// 生成模块 `module` 中书写的类型对应的 schema，结构体放入 components.schemas 并返回 $ref
+    fn schema_for(&mut self, shape: &TypeShape, module: &[String]) -> Value {
+        match shape {
+            TypeShape::Primitive(ty, Some(format)) => json!({ "type": ty, "format": format }),
+            TypeShape::Primitive(ty, None) => json!({ "type": ty }),
+            TypeShape::Unsigned(format) => {
+                json!({ "type": "integer", "format": format, "minimum": 0 })
+            }
+            TypeShape::Optional(inner) => {
+                let inner = self.schema_for(inner, module);
+                json!({ "oneOf": [inner, { "type": "null" }] })
+            }
+            TypeShape::Array(inner) => {
+                json!({ "type": "array", "items": self.schema_for(inner, module) })
+            }
+            TypeShape::Tuple(items) => {
+                let items: Vec<Value> = items
+                    .iter()
+                    .map(|item| self.schema_for(item, module))
+                    .collect();
+                json!({ "type": "array", "prefixItems": items })
+            }
+            TypeShape::Named(type_name) => {
+                let schema = self.lookup(type_name, module).cloned();
+                // 没有扫描到定义的类型按书写的路径命名
+                let name = match &schema {
+                    Some(schema) => self.component_name(schema),
+                    None => type_name.replace("::", "."),
+                };
+                if !self.referenced.contains_key(&name) {
+                    // 先占位，避免自引用结构体无限递归
+                    self.referenced.insert(name.clone(), json!({}));
+                    let value = self.struct_schema(&name, schema.as_ref());
+                    self.referenced.insert(name.clone(), value);
+                }
+                json!({ "$ref": format!("#/components/schemas/{}", name) })
+            }
+            TypeShape::Unknown => json!({}),
+        }
+    }
+
+    fn struct_schema(&mut self, name: &str, schema: Option<&SchemaStruct>) -> Value {
+        let Some(schema) = schema else {
+            return json!({ "type": "object", "title": name });
+        };
+
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        for (field, shape, docs) in &schema.fields {
+            // 字段类型按结构体所在模块中的 use 声明解析
+            let mut property = self.schema_for(shape, &schema.module);
+            if !docs.is_empty() {
+                property["description"] = json!(docs.join("\n"));
+            }
+            properties.insert(field.clone(), property);
+            if !matches!(shape, TypeShape::Optional(_)) {
+                required.push(field.clone());
+            }
+        }
+
+        let mut result = json!({
+            "type": "object",
+            "title": schema.name,
+            "properties": properties,
+            "required": required,
+        });
+        if !schema.docs.is_empty() {
+            result["description"] = json!(schema.docs.join("\n"));
+        }
+        result
+    }
+}
@@ -0,0 +1,215 @@
+use route_codegen_runtime::scope::ScopeRules;
+use std::collections::BTreeMap;
+
+/// 模块路径到 URL 前缀的映射规则
+///
+/// 可以写在调用宏的 crate 的 Cargo.toml 中，也可以通过宏参数 / [`crate::Generator`] 指定，
+/// 后者按字段覆盖前者：
+///
+/// ```toml
+/// [package.metadata.route_codegen]
+/// strip = ["handler", "api"]   # 去掉模块路径开头的这些模块
+/// collapse_duplicates = true   # agency/agency_api -> agency，api_tool/api_tool -> api_tool
+/// kebab_case = true            # tool_info -> tool-info
+///
+/// [package.metadata.route_codegen.mount]
+/// api_tool = "/tools"          # 依赖 crate 挂载的位置，"" 表示挂在根上
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PrefixRules {
+    pub strip: Option<Vec<String>>, // 去掉的开头模块（可以是 a::b 形式的多段路径）
+    pub collapse_duplicates: Option<bool>, // 合并重复的相邻段
+    pub kebab_case: Option<bool>,   // 模块名中的 _ 转换为 -
+    pub mount: BTreeMap<String, String>, // crate 名称 -> 挂载前缀
+}
+
+impl PrefixRules {
+    /// 读取 `[package.metadata.route_codegen]`
+    pub fn from_metadata(metadata: &toml::Value) -> Result<Self, String> {
+        let mut rules = PrefixRules::default();
+        let Some(table) = metadata.as_table() else {
+            return Err("`package.metadata.route_codegen` must be a table".to_string());
+        };
+
+        for (key, value) in table {
+            match key.as_str() {
+                "strip" => {
+                    let strip = value
+                        .as_array()
+                        .and_then(|items| {
+                            items
+                                .iter()
+                                .map(|item| item.as_str().map(String::from))
+                                .collect::<Option<Vec<_>>>()
+                        })
+                        .ok_or("`strip` must be an array of module paths")?;
+                    rules.strip = Some(strip);
+                }
+                "collapse_duplicates" => {
+                    rules.collapse_duplicates = Some(
+                        value
+                            .as_bool()
+                            .ok_or("`collapse_duplicates` must be a boolean")?,
+                    );
+                }
+                "kebab_case" => {
+                    rules.kebab_case =
+                        Some(value.as_bool().ok_or("`kebab_case` must be a boolean")?);
+                }
+                "mount" => {
+                    let mount = value
+                        .as_table()
+                        .ok_or("`mount` must be a table of crate name = \"/prefix\"")?;
+                    for (crate_name, prefix) in mount {
+                        let prefix = prefix.as_str().ok_or_else(|| {
+                            format!("mount prefix of `{}` must be a string", crate_name)
+                        })?;
+                        rules.set_mount(crate_name, prefix)?;
+                    }
+                }
+                // 其他键留给 route_codegen 的其他配置使用
+                _ => {}
+            }
+        }
+        Ok(rules)
+    }
+
+    /// 设置依赖 crate 的挂载前缀
+    pub fn set_mount(&mut self, crate_name: &str, prefix: &str) -> Result<(), String> {
+        if !prefix.is_empty() && !prefix.starts_with('/') {
+            return Err(format!(
+                "mount prefix of `{}` must start with `/`: {:?}",
+                crate_name, prefix
+            ));
+        }
+        self.mount.insert(
+            crate_name.replace('-', "_"),
+            prefix.trim_end_matches('/').to_string(),
+        );
+        Ok(())
+    }
+
+    /// 合并规则：self 中已设置的字段优先
+    pub fn or(mut self, fallback: PrefixRules) -> PrefixRules {
+        self.strip = self.strip.or(fallback.strip);
+        self.collapse_duplicates = self.collapse_duplicates.or(fallback.collapse_duplicates);
+        self.kebab_case = self.kebab_case.or(fallback.kebab_case);
+        for (crate_name, prefix) in fallback.mount {
+            self.mount.entry(crate_name).or_insert(prefix);
+        }
+        self
+    }
+
+    /// 计算模块对应的 URL 前缀，空字符串表示根
+    ///
+    /// `crate_name` 为依赖 crate 的名称（调用宏的 crate 自身为 None），
+    /// `module_path` 为该 crate 内的模块路径段。
+    pub fn scope(&self, crate_name: Option<&str>, module_path: &[String]) -> String {
+        self.with_scope_rules(|rules| rules.scope(crate_name, module_path))
+    }
+
+    /// 在 `base` 后追加模块路径段（合并重复段、转换 kebab-case，不去掉开头的模块）
+    pub fn relative_scope(&self, base: &str, module_path: &[String]) -> String {
+        self.with_scope_rules(|rules| rules.relative_scope(base, module_path))
+    }
+
+    /// 转换为运行时使用的规则，未设置的字段使用默认值
+    pub fn with_scope_rules<T>(&self, f: impl FnOnce(&ScopeRules) -> T) -> T {
+        let strip: Vec<&str> = self.strip.iter().flatten().map(String::as_str).collect();
+        let mount: Vec<(&str, &str)> = self
+            .mount
+            .iter()
+            .map(|(crate_name, prefix)| (crate_name.as_str(), prefix.as_str()))
+            .collect();
+        f(&ScopeRules {
+            strip: &strip,
+            collapse_duplicates: self.collapse_duplicates.unwrap_or(false),
+            kebab_case: self.kebab_case.unwrap_or(false),
+            mount: &mount,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(metadata: &str) -> PrefixRules {
+        PrefixRules::from_metadata(&toml::from_str(metadata).unwrap()).unwrap()
+    }
+
+    fn path(module: &str) -> Vec<String> {
+        module.split("::").map(String::from).collect()
+    }
+
+    #[test]
+    fn default_rules_keep_module_path() {
+        let rules = PrefixRules::default();
+        assert_eq!(
+            rules.scope(None, &path("handler::tool_info")),
+            "/handler/tool_info"
+        );
+        assert_eq!(rules.scope(None, &[]), "");
+        assert_eq!(rules.scope(Some("api_tool"), &path("api")), "/api_tool/api");
+    }
+
+    #[test]
+    fn strips_longest_matching_prefix() {
+        let rules = rules(r#"strip = ["handler", "handler::v1", "api"]"#);
+        assert_eq!(rules.scope(None, &path("handler::v1::user")), "/user");
+        assert_eq!(rules.scope(None, &path("handler::v2::user")), "/v2/user");
+        assert_eq!(rules.scope(None, &path("apis::user")), "/apis/user");
+        // 依赖 crate 内的模块路径同样去掉开头的模块，挂载前缀不受影响
+        assert_eq!(
+            rules.scope(Some("api_tool"), &path("api::x")),
+            "/api_tool/x"
+        );
+    }
+
+    #[test]
+    fn collapses_duplicates_and_converts_kebab_case() {
+        let rules = rules("collapse_duplicates = true\nkebab_case = true");
+        assert_eq!(
+            rules.scope(None, &path("agency::agency_api::tool_info")),
+            "/agency/tool-info"
+        );
+        assert_eq!(rules.scope(None, &path("user::user")), "/user");
+        assert_eq!(rules.scope(None, &path("user::users")), "/user/users");
+        assert_eq!(rules.scope(Some("api_tool"), &path("x")), "/api-tool/x");
+        assert_eq!(rules.relative_scope("/base", &path("a::a_b")), "/base/a");
+    }
+
+    #[test]
+    fn mounts_dependency_crates() {
+        let rules = rules("[mount]\napi-tool = \"/tools/\"\nroot_crate = \"\"");
+        assert_eq!(rules.scope(Some("api_tool"), &path("api")), "/tools/api");
+        assert_eq!(rules.scope(Some("root_crate"), &path("x")), "/x");
+        assert_eq!(rules.scope(Some("other"), &path("x")), "/other/x");
+    }
+
+    #[test]
+    fn rejects_invalid_metadata() {
+        let error = |metadata: &str| {
+            PrefixRules::from_metadata(&toml::from_str(metadata).unwrap()).unwrap_err()
+        };
+        assert!(error("strip = \"handler\"").contains("`strip`"));
+        assert!(error("kebab_case = 1").contains("`kebab_case`"));
+        assert!(error("[mount]\napi_tool = \"tools\"").contains("must start with `/`"));
+    }
+
+    #[test]
+    fn explicit_rules_override_metadata() {
+        let explicit = PrefixRules {
+            kebab_case: Some(false),
+            mount: BTreeMap::from([("a".to_string(), "/x".to_string())]),
+            ..Default::default()
+        };
+        let merged = explicit.or(rules(
+            "strip = [\"handler\"]\nkebab_case = true\n[mount]\na = \"/y\"\nb = \"/z\"",
+        ));
+        assert_eq!(merged.strip, Some(vec!["handler".to_string()]));
+        assert_eq!(merged.kebab_case, Some(false));
+        assert_eq!(merged.mount["a"], "/x");
+        assert_eq!(merged.mount["b"], "/z");
+    }
+}
@@ -0,0 +1,210 @@
+use crate::codegen_attr::is_codegen_attribute;
+use crate::configure_builder::{CodegenOptions, ModuleGroup, ScopeStrategy};
+use crate::diagnostics::Diagnostics;
+use crate::module_fn::{module_chain, ModuleFn};
+use crate::type_path::{self, TypeResolver};
+use quote::{format_ident, quote};
+use serde::{Deserialize, Serialize};
+use syn::{GenericArgument, ItemFn, PathArguments, ReturnType, Type};
+
+/// 模块中标注了 `#[route_provider]` 的函数，为该模块及其子模块中的 handler 提供 app data
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteProvider {
+    pub function: ModuleFn, // provider 函数及其所在模块
+    pub data_type: String,  // 提供的数据类型（源码中书写的形式，如 state::AppState）
+    pub factory: bool,      // 返回 T 而不是 web::Data<T>，注册时用 web::Data::new 包装
+}
+
+/// 读取函数上的 #[route_provider]，未标注时返回 None，签名不支持时返回错误
+///
+/// 返回 `web::Data<T>` 的函数直接注册它的返回值；返回其他类型 `T` 的函数是工厂函数，
+/// 返回值用 `web::Data::new` 包装后注册。两者都提供 `web::Data<T>`。
+pub fn parse_route_provider(fn_item: &ItemFn) -> Option<Result<RouteProvider, String>> {
+    if !fn_item
+        .attrs
+        .iter()
+        .any(|attr| is_codegen_attribute(attr, "route_provider"))
+    {
+        return None;
+    }
+
+    let sig = &fn_item.sig;
+    if sig.asyncness.is_some() || !sig.inputs.is_empty() {
+        return Some(Err(format!(
+            "#[route_provider] `{}` must be a synchronous function without parameters",
+            sig.ident
+        )));
+    }
+    let ReturnType::Type(_, ty) = &sig.output else {
+        return Some(Err(format!(
+            "#[route_provider] `{}` must return web::Data<T> or the data itself",
+            sig.ident
+        )));
+    };
+    if matches!(**ty, Type::ImplTrait(_)) {
+        return Some(Err(format!(
+            "#[route_provider] `{}` must return a concrete type, not `impl Trait`",
+            sig.ident
+        )));
+    }
+
+    let (data_type, factory) = match data_type(ty) {
+        Some(inner) => (written_type(inner), false),
+        None => (written_type(ty), true),
+    };
+    Some(Ok(RouteProvider {
+        function: ModuleFn::new(fn_item),
+        data_type,
+        factory,
+    }))
+}
+
+/// web::Data<T> / Data<T> 中的 T
+pub fn data_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Data" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+/// 源码中书写的类型，去掉 token 之间的空白（如 Mutex<state::AppState>）
+pub fn written_type(ty: &Type) -> String {
+    quote!(#ty).to_string().split_whitespace().collect()
+}
+
+/// 用于比较的类型名称：去掉所有路径前缀（crate::state::AppState -> AppState，
+/// Mutex<std::vec::Vec<u8>> -> Mutex<Vec<u8>>），handler 和 provider 可以用不同的路径引用同一类型
+pub fn type_key(ty: &Type) -> String {
+    let tokens = quote!(#ty).to_string();
+    let tokens: Vec<&str> = tokens.split_whitespace().collect();
+    let mut key = String::new();
+    let mut index = 0;
+    while index < tokens.len() {
+        if tokens[index] == "::" {
+            index += 1;
+        } else if tokens.get(index + 1) == Some(&"::") {
+            index += 2;
+        } else {
+            key.push_str(tokens[index]);
+            index += 1;
+        }
+    }
+    key
+}
+
+/// 入口函数中第 index 个 provider 的变量名
+fn provider_ident(index: usize) -> syn::Ident {
+    format_ident!("__route_provider_{}", index)
+}
+
+/// 在入口函数开头调用所有 provider，每次 configure 中每个 provider 只调用一次，
+/// 各个 scope 共享同一个 `web::Data`
+pub fn provider_lets(
+    providers: &[RouteProvider],
+    options: &CodegenOptions,
+) -> proc_macro2::TokenStream {
+    let lets = providers.iter().enumerate().map(|(index, provider)| {
+        let ident = provider_ident(index);
+        let path = provider.function.path(options);
+        if provider.factory {
+            quote! { let #ident = actix_web::web::Data::new(#path()); }
+        } else {
+            quote! { let #ident = #path(); }
+        }
+    });
+    quote! {
+        #(#lets)*
+    }
+}
+
+/// 分组所需 provider 在入口函数中的变量
+pub fn provider_idents(chain: &[RouteProvider], providers: &[RouteProvider]) -> Vec<syn::Ident> {
+    chain
+        .iter()
+        .filter_map(|provider| providers.iter().position(|p| p == provider))
+        .map(provider_ident)
+        .collect()
+}
+
+/// 生成 `.app_data(..)` 调用链
+pub fn app_data_tokens(
+    chain: &[RouteProvider],
+    providers: &[RouteProvider],
+) -> proc_macro2::TokenStream {
+    let idents = provider_idents(chain, providers);
+    quote! {
+        #(.app_data(#idents.clone()))*
+    }
+}
+
+/// 检查 handler 提取的每个 `web::Data<T>` 都有可达的 provider，或由手写代码注册（`external`）
+///
+/// 缺少 provider 的数据可能是通过 `App::app_data` 注册的，默认只报告警告，strict 模式下为错误。
+/// module / nested 模式下 provider 注册在所在模块的 scope 上，只对该模块及其子模块可见；
+/// flat 模式下所有 provider 注册在入口函数上，对全部 handler 可见。
+/// 类型按各自所在模块的 `use` 声明解析为完整路径后比较，无法解析时比较去掉路径前缀的名称。
+pub fn check_providers(
+    groups: &[ModuleGroup],
+    providers: &[RouteProvider],
+    external: &[String],
+    resolver: &TypeResolver,
+    options: &CodegenOptions,
+    diagnostics: &Diagnostics,
+) {
+    let crate_module = vec!["crate".to_string()];
+    let mut external_keys = Vec::new();
+    for ty in external {
+        match syn::parse_str::<Type>(ty) {
+            Ok(_) => external_keys.push(resolver.key(ty, &crate_module)),
+            Err(e) => diagnostics.problem(format!("invalid external data type `{}`: {}", ty, e)),
+        }
+    }
+
+    for group in groups {
+        for function in &group.functions {
+            let module_path = crate::configure_builder::module_segments(&function.module_prefix);
+            let reachable = if options.scope == ScopeStrategy::Flat {
+                providers.to_vec()
+            } else {
+                module_chain(&function.crate_root, &module_path, providers, |p| {
+                    &p.function
+                })
+            };
+            let handler_module =
+                type_path::module_path(&function.crate_root, &function.module_prefix);
+            for data in &function.signature.data {
+                let key = resolver.key(data, &handler_module);
+                let provided = reachable.iter().any(|provider| {
+                    let provider_module = type_path::module_path(
+                        &provider.function.crate_root,
+                        &provider.function.module_prefix,
+                    );
+                    key.matches(&resolver.key(&provider.data_type, &provider_module))
+                });
+                if provided || external_keys.iter().any(|external| key.matches(external)) {
+                    continue;
+                }
+                diagnostics.problem(format!(
+                    "{}: handler `{}` extracts web::Data<{}>, but no #[route_provider] for `{}` is declared in module `{}` or its parents; \
+                     declare data registered by hand with `external_data = [\"{}\"]`",
+                    function.location(),
+                    function.name,
+                    data,
+                    key.path.as_deref().unwrap_or(&key.name),
+                    function.module_prefix,
+                    key.path.as_deref().unwrap_or(&key.name)
+                ));
+            }
+        }
+    }
+}
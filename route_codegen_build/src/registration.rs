@@ -0,0 +1,274 @@
+use crate::codegen_attr::{cfg_attribute, is_codegen_attribute, is_skipped};
+use crate::configure_builder::{CodegenOptions, ScopeStrategy};
+use crate::crate_targets::CrateTargets;
+use crate::dependencies::read_path_dependencies;
+use crate::dependency_tracker::DependencyTracker;
+use crate::diagnostics::Diagnostics;
+use crate::scan_cache::ScanCache;
+use crate::{extract_route_info, read_package_name};
+use quote::quote;
+use std::path::Path;
+use syn::{Ident, ItemFn};
+
+/// 路由的登记方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Registration {
+    /// 宏展开时扫描源码文件，生成每个 handler 的注册代码
+    #[default]
+    Scan,
+    /// 不扫描源码：handler 通过 `#[route_codegen::register]` 在链接时登记自身，
+    /// 生成的 configure 在启动时遍历登记的 handler
+    Static,
+}
+
+impl std::str::FromStr for Registration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "scan" => Ok(Registration::Scan),
+            "static" => Ok(Registration::Static),
+            _ => Err(format!(
+                "unknown registration `{}`; expected \"scan\" or \"static\"",
+                s
+            )),
+        }
+    }
+}
+
+/// 展开 `#[route_codegen::register]`：原样保留 handler，并生成在链接时登记它的静态数据
+///
+/// 属性必须写在路由属性（`#[get]`、`#[route]`、`#[routes]` 等）之上，
+/// 这样展开时仍能读取到路由属性；handler 上的 `#[cfg]` 同样作用于登记代码。
+pub fn expand_register(fn_item: &ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+    if is_skipped(&fn_item.attrs) {
+        return Err(syn::Error::new_spanned(
+            &fn_item.sig.ident,
+            "#[route_codegen::register] cannot be combined with #[route_codegen::skip]",
+        ));
+    }
+    let Some(route_fn) = extract_route_info(fn_item)? else {
+        return Err(syn::Error::new_spanned(
+            &fn_item.sig.ident,
+            "#[route_codegen::register] must be placed above a route attribute such as #[get(\"/path\")]",
+        ));
+    };
+
+    let ident = &fn_item.sig.ident;
+    let name = &route_fn.name;
+    let line = route_fn.line as u32;
+    let priority = route_fn.priority;
+    // 路由属性外层 cfg_attr 的条件作用于对应的条目，handler 本身的条件作用于整个登记代码
+    let endpoints = route_fn.routes.iter().flat_map(|route| {
+        let cfg_attr = cfg_attribute(&route.cfgs);
+        let path = &route.path;
+        route.methods.iter().map(move |method| {
+            quote! {
+                #cfg_attr
+                route_codegen_runtime::registry::StaticEndpoint { method: #method, path: #path }
+            }
+        })
+    });
+    let cfg_attr = cfg_attribute(&route_fn.cfgs);
+
+    Ok(quote! {
+        #fn_item
+
+        #cfg_attr
+        const _: () = {
+            fn __route_codegen_register(cfg: &mut actix_web::web::ServiceConfig) {
+                cfg.service(#ident);
+            }
+
+            route_codegen_runtime::submit!(route_codegen_runtime::registry::StaticRoute {
+                name: #name,
+                module_path: module_path!(),
+                file: file!(),
+                line: #line,
+                priority: #priority,
+                endpoints: &[#(#endpoints),*],
+                register: __route_codegen_register,
+            });
+        };
+    })
+}
+
+/// 检查 handler 上的标记属性（`#[priority]`、`#[skip]`）是否写在 `#[register]` 之下
+///
+/// 属性按从上到下的顺序展开，写在 `#[register]` 之上的标记属性会先被移除，
+/// 登记代码读取不到它，静态注册的顺序或结果就会与扫描不同。
+pub fn check_marker_below_register(
+    marker: &str,
+    item: proc_macro2::TokenStream,
+) -> syn::Result<()> {
+    let Ok(fn_item) = syn::parse2::<ItemFn>(item) else {
+        return Ok(());
+    };
+    match fn_item
+        .attrs
+        .iter()
+        .find(|attr| is_codegen_attribute(attr, "register"))
+    {
+        Some(attr) => Err(syn::Error::new_spanned(
+            attr,
+            format!(
+                "#[route_codegen::{}] must be written below #[route_codegen::register] so that the registration can read it",
+                marker
+            ),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// 需要强制链接的 crate：名称，以及启用它所需的 cfg 条件
+pub struct LinkedCrate {
+    pub name: String,      // 调用宏的 crate 中引用它使用的名称
+    pub lib_name: String,  // 库名（module_path!() 的第一段），依赖重命名时与 name 不同
+    pub cfgs: Vec<String>, // optional / target 依赖的条件
+}
+
+/// 可能包含 `#[route_codegen::register]` 的 crate：调用宏的 crate 的 path 依赖，
+/// 以及生成代码位于可执行目标中时同一个包的库目标
+///
+/// 没有被引用的依赖不会被链接，其中登记的 handler 也就不存在，
+/// 生成的代码用 `extern crate .. as _;` 保证它们被链接。
+pub fn linked_crates(
+    manifest_dir: &Path,
+    invoked_from: Option<&Path>,
+    tracker: &DependencyTracker,
+    diagnostics: &Diagnostics,
+) -> Vec<LinkedCrate> {
+    let mut linked = Vec::new();
+    // 库目标中的 handler 同样需要链接到可执行目标
+    if let Ok(targets) = CrateTargets::read(manifest_dir)
+        && let Some(lib) = &targets.lib
+        && !targets.proc_macro
+        && targets
+            .local_root(invoked_from, tracker, &ScanCache::in_memory())
+            .as_ref()
+            != Some(lib)
+        && let Ok(package_name) = read_package_name(&manifest_dir.join("Cargo.toml"))
+    {
+        let lib_name = targets
+            .lib_name
+            .as_deref()
+            .unwrap_or(&package_name)
+            .replace('-', "_");
+        linked.push(LinkedCrate {
+            name: lib_name.clone(),
+            lib_name,
+            cfgs: Vec::new(),
+        });
+    }
+
+    let dependencies = match read_path_dependencies(manifest_dir, diagnostics) {
+        Ok(dependencies) => dependencies,
+        Err(e) => {
+            diagnostics.problem(e);
+            return linked;
+        }
+    };
+    for dependency in dependencies {
+        let manifest_path = dependency.dir.join("Cargo.toml");
+        tracker.track(&manifest_path);
+        match CrateTargets::read(&dependency.dir) {
+            // 过程宏 crate 和只有可执行目标的 crate 不能登记 handler
+            Ok(CrateTargets {
+                lib: Some(_),
+                lib_name,
+                proc_macro: false,
+                ..
+            }) => linked.push(LinkedCrate {
+                name: dependency.crate_name(lib_name.as_deref()),
+                lib_name: lib_name
+                    .as_deref()
+                    .unwrap_or(&dependency.package)
+                    .replace('-', "_"),
+                cfgs: dependency.cfgs.clone(),
+            }),
+            Ok(_) => {}
+            Err(e) => diagnostics.problem(format!("dependency `{}`: {}", dependency.key, e)),
+        }
+    }
+    linked
+}
+
+/// 静态注册的入口函数和 `ROUTES`：分组、排序和前缀计算都在运行时按与扫描相同的规则进行
+///
+/// `file_prefix` 为调用宏的 crate 相对 workspace 根的目录（如 `services/web/`），
+/// 用于把 `file!()` 转换为与扫描结果相同的相对路径。
+pub fn build_static_configure(
+    options: &CodegenOptions,
+    file_prefix: &str,
+    linked: &[LinkedCrate],
+) -> proc_macro2::TokenStream {
+    let configure_ident = Ident::new(&options.name, proc_macro2::Span::call_site());
+    let flat = options.scope == ScopeStrategy::Flat;
+    let prefix = &options.prefix;
+    let strip = options.rules.strip.iter().flatten();
+    let collapse_duplicates = options.rules.collapse_duplicates.unwrap_or(false);
+    let kebab_case = options.rules.kebab_case.unwrap_or(false);
+    let mount = options
+        .rules
+        .mount
+        .iter()
+        .map(|(crate_name, prefix)| quote! { (#crate_name, #prefix) });
+
+    // module_path!() 中是依赖的库名，重命名的依赖在路由表和 mount 规则中使用依赖的键
+    let crate_names = linked
+        .iter()
+        .filter(|krate| krate.lib_name != krate.name)
+        .map(|krate| {
+            let (lib_name, name) = (&krate.lib_name, &krate.name);
+            quote! { (#lib_name, #name) }
+        });
+    let linked = linked.iter().map(|krate| {
+        let name = Ident::new(&krate.name, proc_macro2::Span::call_site());
+        let cfg_attr = cfg_attribute(&krate.cfgs);
+        quote! {
+            #cfg_attr
+            extern crate #name as _;
+        }
+    });
+
+    quote! {
+        route_codegen_runtime::assert_supported_target!();
+
+        #(#linked)*
+
+        static ROUTE_OPTIONS: route_codegen_runtime::registry::Options =
+            route_codegen_runtime::registry::Options {
+                crate_name: env!("CARGO_CRATE_NAME"),
+                crate_names: &[#(#crate_names),*],
+                file_prefix: #file_prefix,
+                flat: #flat,
+                prefix: #prefix,
+                rules: route_codegen_runtime::scope::ScopeRules {
+                    strip: &[#(#strip),*],
+                    collapse_duplicates: #collapse_duplicates,
+                    kebab_case: #kebab_case,
+                    mount: &[#(#mount),*],
+                },
+            };
+
+        /// 按注册顺序排列的全部路由（第一次访问时根据登记的 handler 计算）
+        #[allow(dead_code)]
+        pub static ROUTES: route_codegen_runtime::registry::Routes =
+            route_codegen_runtime::registry::Routes::new(&ROUTE_OPTIONS);
+
+        pub fn #configure_ident(cfg: &mut actix_web::web::ServiceConfig) {
+            {
+                use std::sync::atomic::{AtomicBool, Ordering};
+                static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+                if INITIALIZED.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+                    for route in ROUTES.iter() {
+                        log::info!("🚀 Registered route: {} {}", route.method, route.path);
+                    }
+                }
+            }
+
+            ROUTE_OPTIONS.configure(cfg);
+        }
+    }
+}
@@ -0,0 +1,385 @@
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use syn::punctuated::Punctuated;
+use syn::{Attribute, LitStr, Meta, MetaNameValue, Token};
+
+/// 单个路由属性（如 #[get("/x", name = "x", wrap = "Logger::default()")]）解析出的元数据
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RouteAttribute {
+    pub methods: Vec<String>, // HTTP 方法（大写，如 GET、POST）
+    pub path: String,         // 路由路径（如 /api/test）
+    pub name: Option<String>, // 资源名称（name = "..."）
+    pub guards: Vec<String>,  // 守卫（guard = "..."）
+    pub wraps: Vec<String>,   // 中间件表达式（wrap = "..."）
+    #[serde(default)]
+    pub cfgs: Vec<String>, // 包裹该属性的 #[cfg_attr] 条件，只作用于这条路由
+}
+
+/// 支持的 HTTP 方法列表（属性名 -> HTTP 方法）
+const METHOD_MAP: &[(&str, &str)] = &[
+    ("get", "GET"),
+    ("post", "POST"),
+    ("put", "PUT"),
+    ("delete", "DELETE"),
+    ("head", "HEAD"),
+    ("connect", "CONNECT"),
+    ("options", "OPTIONS"),
+    ("trace", "TRACE"),
+    ("patch", "PATCH"),
+];
+
+/// 路由属性的种类
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RouteAttrKind {
+    Method(&'static str), // #[get(...)] 等单方法属性
+    Route,                // #[route("/p", method = "GET", ...)]
+    Routes,               // #[routes]，与多个方法属性叠加使用
+}
+
+/// 识别属性种类，支持简写形式 #[get(...)] 和全路径形式 #[actix_web::get(...)]
+fn route_attr_kind(attr: &Attribute) -> Option<RouteAttrKind> {
+    let key = get_attr_key(attr)?;
+    match key.as_str() {
+        "route" => Some(RouteAttrKind::Route),
+        "routes" => Some(RouteAttrKind::Routes),
+        _ => METHOD_MAP
+            .iter()
+            .find(|&&(k, _)| k == key)
+            .map(|&(_, method)| RouteAttrKind::Method(method)),
+    }
+}
+
+/// 提取属性宏的标识符名称（去掉 actix_web:: 前缀）
+fn get_attr_key(attr: &Attribute) -> Option<String> {
+    let segments: Vec<_> = attr.path().segments.iter().collect();
+    match segments.as_slice() {
+        [ident] => Some(ident.ident.to_string()),
+        [krate, ident] if krate.ident == "actix_web" => Some(ident.ident.to_string()),
+        _ => None,
+    }
+}
+
+/// 解析函数上的全部路由属性
+///
+/// 返回 `Ok(vec![])` 表示函数不是 handler；无法解析的属性返回错误，而不是被悄悄丢弃。
+/// `#[cfg_attr(条件, get(..))]` 中的路由属性同样会被识别，条件记录在 [`RouteAttribute::cfgs`] 中；
+/// 第二个返回值为注册 handler 本身需要的条件（`#[routes]` 或单个路由属性外层的 `cfg_attr`）。
+pub fn parse_route_attributes(
+    attrs: &[Attribute],
+) -> syn::Result<(Vec<RouteAttribute>, Vec<String>)> {
+    let attrs = expand_cfg_attrs(attrs);
+    let mut routes = Vec::new();
+    let mut route_attrs = Vec::new();
+    let mut routes_attr = None;
+
+    for (attr, cfgs) in &attrs {
+        match route_attr_kind(attr) {
+            Some(RouteAttrKind::Method(method)) => {
+                let mut route = parse_route_attribute(attr, Some(method))?;
+                route.cfgs = cfgs.clone();
+                routes.push(route);
+                route_attrs.push(attr);
+            }
+            Some(RouteAttrKind::Route) => {
+                let mut route = parse_route_attribute(attr, None)?;
+                route.cfgs = cfgs.clone();
+                routes.push(route);
+                route_attrs.push(attr);
+            }
+            Some(RouteAttrKind::Routes) => {
+                if !matches!(attr.meta, Meta::Path(_)) {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "#[routes] does not take any arguments",
+                    ));
+                }
+                routes_attr = Some((attr, cfgs));
+            }
+            None => {}
+        }
+    }
+
+    // 多个路由属性都带有 cfg_attr 条件时（如按 feature 选择不同的方法），可以不使用 #[routes]
+    let unconditional = routes.iter().any(|route| route.cfgs.is_empty());
+    match routes_attr {
+        Some((attr, _)) if routes.is_empty() => {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "#[routes] requires at least one `#[<method>(..)]` attribute",
+            ));
+        }
+        None if route_attrs.len() > 1 && unconditional => {
+            return Err(syn::Error::new_spanned(
+                route_attrs[1],
+                "multiple route attributes on one handler require #[routes]",
+            ));
+        }
+        _ => {}
+    }
+
+    let service_cfgs = match routes_attr {
+        Some((_, cfgs)) => cfgs.clone(),
+        None if unconditional => Vec::new(),
+        None => match routes.as_slice() {
+            [route] => route.cfgs.clone(),
+            _ => {
+                let predicates: Vec<String> = routes
+                    .iter()
+                    .map(|route| format!("all({})", route.cfgs.join(", ")))
+                    .collect();
+                vec![format!("any({})", predicates.join(", "))]
+            }
+        },
+    };
+    Ok((routes, service_cfgs))
+}
+
+/// 展开路由属性外层的 `#[cfg_attr(条件, 属性, ..)]`（可嵌套），返回属性及包裹它的条件
+///
+/// 过程宏无法计算条件，条件原样保留，由编译器在生成的代码上判断。
+fn expand_cfg_attrs(attrs: &[Attribute]) -> Vec<(Attribute, Vec<String>)> {
+    let mut expanded = Vec::new();
+    for attr in attrs {
+        expand_cfg_attr(attr, &[], &mut expanded);
+    }
+    expanded
+}
+
+fn expand_cfg_attr(
+    attr: &Attribute,
+    cfgs: &[String],
+    expanded: &mut Vec<(Attribute, Vec<String>)>,
+) {
+    if !attr.path().is_ident("cfg_attr") {
+        if route_attr_kind(attr).is_some() {
+            expanded.push((attr.clone(), cfgs.to_vec()));
+        }
+        return;
+    }
+    // 无法解析的 cfg_attr 由编译器报告
+    let Ok((predicate, metas)) = attr.parse_args_with(|input: syn::parse::ParseStream| {
+        let predicate: Meta = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let metas = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
+        Ok((predicate, metas))
+    }) else {
+        return;
+    };
+    let cfgs = [cfgs, &[predicate.to_token_stream().to_string()]].concat();
+    for meta in metas {
+        let inner = Attribute {
+            meta,
+            ..attr.clone()
+        };
+        expand_cfg_attr(&inner, &cfgs, expanded);
+    }
+}
+
+/// 路由属性的参数：路径 + 可选的 key = "value" 选项
+struct RouteArgs {
+    path: LitStr,
+    options: Punctuated<MetaNameValue, Token![,]>,
+}
+
+impl syn::parse::Parse for RouteArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let path: LitStr = input.parse().map_err(|e| {
+            syn::Error::new(
+                e.span(),
+                r#"invalid service definition, expected #[<method>("<path>")]"#,
+            )
+        })?;
+
+        if input.is_empty() {
+            return Ok(RouteArgs {
+                path,
+                options: Punctuated::new(),
+            });
+        }
+        input.parse::<Token![,]>()?;
+
+        if input.peek(LitStr) {
+            return Err(input.error("Multiple paths specified! There should be only one."));
+        }
+
+        let options = Punctuated::parse_terminated(input)?;
+        Ok(RouteArgs { path, options })
+    }
+}
+
+/// 解析单个路由属性的参数；`method` 为 None 时表示 #[route(...)]
+fn parse_route_attribute(attr: &Attribute, method: Option<&str>) -> syn::Result<RouteAttribute> {
+    let args = attr.parse_args::<RouteArgs>()?;
+
+    let mut route = RouteAttribute {
+        methods: method.map(|m| vec![m.to_string()]).unwrap_or_default(),
+        path: args.path.value(),
+        name: None,
+        guards: Vec::new(),
+        wraps: Vec::new(),
+        cfgs: Vec::new(),
+    };
+
+    for option in args.options {
+        let key = option
+            .path
+            .get_ident()
+            .map(|ident| ident.to_string())
+            .unwrap_or_default();
+        let value = string_value(&option)?;
+
+        match key.as_str() {
+            "name" => route.name = Some(value),
+            "guard" => {
+                syn::parse_str::<syn::Path>(&value)?;
+                route.guards.push(value);
+            }
+            "wrap" => {
+                syn::parse_str::<syn::Expr>(&value)?;
+                route.wraps.push(value);
+            }
+            "method" if method.is_some() => {
+                return Err(syn::Error::new_spanned(
+                    &option,
+                    "HTTP method forbidden here; to handle multiple methods, use `route` instead",
+                ));
+            }
+            "method" => {
+                if value.to_uppercase() != value {
+                    return Err(syn::Error::new_spanned(
+                        &option.value,
+                        format!("HTTP method must be uppercase: `{}`", value),
+                    ));
+                }
+                if route.methods.contains(&value) {
+                    return Err(syn::Error::new_spanned(
+                        &option.value,
+                        format!("HTTP method defined more than once: `{}`", value),
+                    ));
+                }
+                route.methods.push(value);
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &option.path,
+                    "Unknown attribute key is specified; allowed: name, guard, method and wrap",
+                ));
+            }
+        }
+    }
+
+    if route.methods.is_empty() {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "The #[route(..)] macro requires at least one `method` attribute",
+        ));
+    }
+
+    Ok(route)
+}
+
+/// 读取 key = "value" 中的字符串字面量
+fn string_value(option: &MetaNameValue) -> syn::Result<String> {
+    match &option.value {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(lit),
+            ..
+        }) => Ok(lit.value()),
+        value => Err(syn::Error::new_spanned(
+            value,
+            "Attribute value expects literal string",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::ItemFn;
+
+    fn parse(function: &str) -> syn::Result<(Vec<RouteAttribute>, Vec<String>)> {
+        let function: ItemFn = syn::parse_str(function).unwrap();
+        parse_route_attributes(&function.attrs)
+    }
+
+    fn routes(function: &str) -> Vec<(String, Vec<String>)> {
+        parse(function)
+            .unwrap()
+            .0
+            .into_iter()
+            .map(|route| (route.path, route.cfgs))
+            .collect()
+    }
+
+    #[test]
+    fn plain_route_has_no_cfgs() {
+        assert_eq!(
+            parse(r#"#[get("/a")] async fn a() {}"#).unwrap().1,
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn cfg_attr_route_gates_the_handler() {
+        let (routes, service_cfgs) =
+            parse(r#"#[cfg_attr(feature = "beta", get("/beta"))] async fn beta() {}"#).unwrap();
+        assert_eq!(routes[0].cfgs, [r#"feature = "beta""#]);
+        assert_eq!(service_cfgs, [r#"feature = "beta""#]);
+    }
+
+    #[test]
+    fn nested_cfg_attrs_are_combined() {
+        assert_eq!(
+            routes(r#"#[cfg_attr(unix, cfg_attr(feature = "beta", post("/x")))] async fn x() {}"#),
+            [(
+                "/x".to_string(),
+                vec!["unix".to_string(), r#"feature = "beta""#.to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn alternative_routes_are_registered_when_any_applies() {
+        let (routes, service_cfgs) = parse(
+            r#"
+            #[cfg_attr(feature = "v1", get("/v1"))]
+            #[cfg_attr(not(feature = "v1"), get("/v2"))]
+            async fn versioned() {}
+            "#,
+        )
+        .unwrap();
+        assert_eq!(routes.len(), 2);
+        assert_eq!(
+            service_cfgs,
+            [r#"any(all(feature = "v1"), all(not (feature = "v1")))"#]
+        );
+    }
+
+    #[test]
+    fn routes_attribute_cfgs_gate_the_handler() {
+        let (routes, service_cfgs) = parse(
+            r#"
+            #[cfg_attr(test, routes)]
+            #[get("/a")]
+            #[cfg_attr(feature = "beta", post("/a"))]
+            async fn a() {}
+            "#,
+        )
+        .unwrap();
+        assert_eq!(routes[1].cfgs, [r#"feature = "beta""#]);
+        assert_eq!(service_cfgs, ["test"]);
+    }
+
+    #[test]
+    fn unconditional_routes_require_routes_attribute() {
+        let error = parse(
+            r#"
+            #[get("/a")]
+            #[cfg_attr(feature = "beta", post("/a"))]
+            async fn a() {}
+            "#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("require #[routes]"));
+    }
+}
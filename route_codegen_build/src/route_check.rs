@@ -0,0 +1,273 @@
+use crate::configure_builder::{collect_endpoints, CodegenOptions, ModuleGroup, ScopeStrategy};
+use crate::diagnostics::Diagnostics;
+use route_codegen_runtime::order::{path_segments, scope_segments};
+
+/// 按注册顺序检查主 crate 和所有依赖 crate 中最终生成的路由表
+///
+/// - 方法和完整路径完全相同（参数只比较位置和正则约束，不比较参数名）的路由报告编译错误；
+/// - 先注册的动态路由（如 /{id}、/{tail:.*}）会遮蔽后注册的路由（如 /new）时报告警告，
+///   带正则约束的参数无法确定能匹配哪些段，不视为遮蔽；
+/// - configure hook 在所在分组的 handler 之后注册，其中以字面量注册的路径被 handler 遮蔽时报告警告；
+/// - 较短的 scope 先于以它为前缀的 scope 注册时报告警告（nested 模式下子 scope 嵌套在父 scope 中，不存在这个问题）。
+pub fn check_routes(groups: &[ModuleGroup], options: &CodegenOptions, diagnostics: &Diagnostics) {
+    if options.scope != ScopeStrategy::Nested {
+        check_scopes(groups, diagnostics);
+    }
+
+    check_hooks(groups, diagnostics);
+
+    let endpoints = collect_endpoints(groups);
+    for (i, first) in endpoints.iter().enumerate() {
+        for second in &endpoints[i + 1..] {
+            // 带 guard 的路由可以合法地共享方法和路径，#[cfg] 条件不同的路由可能不会同时编译
+            let guarded = !first.route.guards.is_empty() || !second.route.guards.is_empty();
+            let conditional =
+                first.function.route_cfgs(first.route) != second.function.route_cfgs(second.route);
+            if first.method != second.method || guarded || conditional {
+                continue;
+            }
+
+            let first_segments = normalize(&path_segments(&first.path));
+            let second_segments = normalize(&path_segments(&second.path));
+            if first_segments == second_segments {
+                diagnostics.error(format!(
+                    "duplicate route {} {}: `{}` ({}) and `{}` ({})",
+                    first.method,
+                    first.path,
+                    first.function.name,
+                    first.function.location(),
+                    second.function.name,
+                    second.function.location()
+                ));
+            } else if shadows(&first_segments, &second_segments) {
+                diagnostics.warn(format!(
+                    "route {} {} (`{}`, {}) is registered before and shadows {} {} (`{}`, {})",
+                    first.method,
+                    first.path,
+                    first.function.name,
+                    first.function.location(),
+                    second.method,
+                    second.path,
+                    second.function.name,
+                    second.function.location()
+                ));
+            }
+        }
+    }
+}
+
+/// hook 在分组内的 handler 之后注册，同一 scope 中能匹配 hook 路径的 handler 路由会先处理请求
+fn check_hooks(groups: &[ModuleGroup], diagnostics: &Diagnostics) {
+    for group in groups {
+        let endpoints = collect_endpoints(std::slice::from_ref(group));
+        for hook in &group.hooks {
+            for path in &hook.paths {
+                let full_path = format!("{}{}", group.scope, path);
+                let hook_segments = normalize(&path_segments(&full_path));
+                for endpoint in &endpoints {
+                    if !shadows(&normalize(&path_segments(&endpoint.path)), &hook_segments) {
+                        continue;
+                    }
+                    diagnostics.warn(format!(
+                        "route {} {} (`{}`, {}) is registered before #[configure_hook] `{}` ({}) and shadows its path {} for {} requests; \
+                         configure hooks run after the module's handlers",
+                        endpoint.method,
+                        endpoint.path,
+                        endpoint.function.name,
+                        endpoint.function.location(),
+                        hook.name,
+                        hook.location(),
+                        full_path,
+                        endpoint.method
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// actix 的 scope 按前缀匹配且匹配后不会回退，先注册的短前缀会吞掉后面的长前缀
+fn check_scopes(groups: &[ModuleGroup], diagnostics: &Diagnostics) {
+    for (i, first) in groups.iter().enumerate() {
+        let first_segments = scope_segments(&first.scope);
+        for second in &groups[i + 1..] {
+            let second_segments = scope_segments(&second.scope);
+            if first_segments.len() < second_segments.len()
+                && second_segments.starts_with(&first_segments)
+            {
+                diagnostics.warn(format!(
+                    "scope {} is registered before {} and will swallow its requests",
+                    first.scope, second.scope
+                ));
+            }
+        }
+    }
+}
+
+/// 去掉参数名后的路径段，用于比较路由形状：`{id}` -> `{}`，`{id:\d+}` -> `{:\d+}`，
+/// `{name}.json` -> `{}.json`；静态段和正则约束原样比较
+fn normalize(segments: &[&str]) -> Vec<String> {
+    segments
+        .iter()
+        .map(|segment| segment_shape(segment))
+        .collect()
+}
+
+fn segment_shape(segment: &str) -> String {
+    let mut shape = String::new();
+    let mut depth = 0;
+    let mut in_name = false;
+    for c in segment.chars() {
+        match c {
+            '{' if depth == 0 => {
+                depth = 1;
+                in_name = true;
+                shape.push(c);
+            }
+            ':' if depth == 1 && in_name => {
+                in_name = false;
+                shape.push(c);
+            }
+            '{' => {
+                depth += 1;
+                shape.push(c);
+            }
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    in_name = false;
+                }
+                shape.push(c);
+            }
+            _ if in_name => {}
+            _ => shape.push(c),
+        }
+    }
+    shape
+}
+
+/// 规范化后的路径段能匹配的内容
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SegmentKind {
+    Literal, // 静态段，或参数与静态文本混合的段，只能按原文比较
+    Param,   // 整段为不带约束的参数（{id}），匹配任意非空段
+    Tail,    // 整段为 {tail:.*} / {tail:.+} / {tail}*，匹配剩余的全部路径
+    Regex,   // 整段为带正则约束的参数（{id:\d+}），无法确定能匹配哪些段
+}
+
+fn segment_kind(shape: &str) -> SegmentKind {
+    match shape {
+        "{}" => SegmentKind::Param,
+        "{:.*}" | "{:.+}" | "{}*" => SegmentKind::Tail,
+        _ if shape.starts_with("{:") && closing_brace(shape) == Some(shape.len() - 1) => {
+            SegmentKind::Regex
+        }
+        _ => SegmentKind::Literal,
+    }
+}
+
+/// 以 `{` 开头的段中，与第一个 `{` 配对的 `}` 的位置
+fn closing_brace(shape: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (index, c) in shape.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 先注册的路径能匹配后注册路径的所有请求：
+/// 每一段要么相同，要么先注册的一方是不带约束的参数；先注册一方的尾部通配匹配剩余的全部路径
+fn shadows(first: &[String], second: &[String]) -> bool {
+    for (index, a) in first.iter().enumerate() {
+        match segment_kind(a) {
+            SegmentKind::Tail => {
+                // {tail:.+} 至少需要一个字符
+                let rest = second.get(index..).unwrap_or_default();
+                return a != "{:.+}" || rest.iter().any(|segment| !segment.is_empty());
+            }
+            _ if second.get(index) == Some(a) => {}
+            SegmentKind::Param => match second.get(index) {
+                Some(b) if !b.is_empty() && segment_kind(b) != SegmentKind::Tail => {}
+                _ => return false,
+            },
+            _ => return false,
+        }
+    }
+    first.len() == second.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(path: &str) -> Vec<String> {
+        normalize(&path_segments(path))
+    }
+
+    fn duplicate(a: &str, b: &str) -> bool {
+        shape(a) == shape(b)
+    }
+
+    fn shadowed(first: &str, second: &str) -> bool {
+        shadows(&shape(first), &shape(second))
+    }
+
+    #[test]
+    fn bare_params_are_compared_by_position() {
+        assert!(duplicate("/users/{id}", "/users/{user_id}"));
+        assert!(!duplicate("/users/{id}", "/users/{id}/"));
+        assert!(!duplicate("/users/{id}", "/users/new"));
+    }
+
+    #[test]
+    fn mixed_segments_are_compared_verbatim() {
+        assert!(!duplicate("/files/{name}.json", "/files/{name}"));
+        assert!(!duplicate("/files/{name}.json", "/files/{name}.xml"));
+        assert!(duplicate("/files/{name}.json", "/files/{file}.json"));
+    }
+
+    #[test]
+    fn regex_constraints_are_compared_verbatim() {
+        assert!(!duplicate(r"/x/{id:\d+}", "/x/{name}"));
+        assert!(!duplicate(r"/x/{id:\d+}", "/x/{name:[a-z]+}"));
+        assert!(duplicate(r"/x/{id:\d+}", r"/x/{num:\d+}"));
+        assert!(duplicate(r"/x/{id:\d{3}}", r"/x/{num:\d{3}}"));
+        assert!(!duplicate(r"/x/{id:\d{3}}", r"/x/{num:\d{4}}"));
+    }
+
+    #[test]
+    fn bare_params_shadow_later_segments() {
+        assert!(shadowed("/{id}", "/new"));
+        assert!(shadowed("/users/{id}", r"/users/{id:\d+}"));
+        assert!(!shadowed("/users/{id}", "/users/{id}/posts"));
+        assert!(!shadowed("/users/{id}", "/users/"));
+        assert!(!shadowed("/new", "/{id}"));
+    }
+
+    #[test]
+    fn constrained_params_do_not_shadow() {
+        assert!(!shadowed(r"/{id:\d+}", "/new"));
+        assert!(!shadowed(r"/{id:\d+}", "/{name}"));
+        assert!(!shadowed("/{name}.json", "/a.json"));
+    }
+
+    #[test]
+    fn tail_params_shadow_any_suffix() {
+        assert!(shadowed("/static/{tail:.*}", "/static/css/app.css"));
+        assert!(shadowed("/static/{tail:.*}", "/static/"));
+        assert!(shadowed("/{tail:.*}", "/a/b/c"));
+        assert!(shadowed("/static/{tail}*", "/static/a/b"));
+        assert!(!shadowed("/static/{tail:.+}", "/static/"));
+        assert!(!shadowed("/static/{tail:.*}", "/assets/a"));
+        assert!(!shadowed("/static/{id}", "/static/{tail:.*}"));
+    }
+}
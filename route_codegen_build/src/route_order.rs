@@ -0,0 +1,117 @@
+use crate::configure_builder::ModuleGroup;
+use crate::RouteFunction;
+use route_codegen_runtime::order::{compare_handlers, compare_scopes, HandlerOrder};
+use std::cmp::Ordering;
+
+/// 对模块分组和组内 handler 排序，保证生成代码在每次构建中完全一致
+///
+/// 排序策略见 [`route_codegen_runtime::order`]，静态注册在运行时使用同样的顺序。
+pub fn sort_module_groups(groups: &mut [ModuleGroup]) {
+    for group in groups.iter_mut() {
+        group.functions.sort_by(compare_functions);
+    }
+    groups.sort_by(|a, b| {
+        group_priority(&b.functions)
+            .cmp(&group_priority(&a.functions))
+            .then_with(|| compare_scopes(&a.scope, &b.scope))
+            .then_with(|| a.module_path.cmp(&b.module_path))
+    });
+}
+
+/// 模块的优先级：取组内 handler 的最大值
+fn group_priority(functions: &[RouteFunction]) -> i32 {
+    functions.iter().map(|f| f.priority).max().unwrap_or(0)
+}
+
+/// 同一模块内：priority 大的在前，然后按路径的具体程度
+fn compare_functions(a: &RouteFunction, b: &RouteFunction) -> Ordering {
+    compare_handlers(&handler_order(a), &handler_order(b))
+}
+
+fn handler_order(function: &RouteFunction) -> HandlerOrder<'_> {
+    HandlerOrder {
+        priority: function.priority,
+        path: function
+            .routes
+            .first()
+            .map(|route| route.path.as_str())
+            .unwrap_or(""),
+        name: &function.name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::route_attr::RouteAttribute;
+
+    fn function(name: &str, path: &str, priority: i32) -> RouteFunction {
+        RouteFunction {
+            name: name.to_string(),
+            routes: vec![RouteAttribute {
+                methods: vec!["GET".to_string()],
+                path: path.to_string(),
+                name: None,
+                guards: Vec::new(),
+                wraps: Vec::new(),
+                cfgs: Vec::new(),
+            }],
+            module_prefix: String::new(),
+            crate_root: "crate".to_string(),
+            source_file: Default::default(),
+            line: 1,
+            priority,
+            signature: Default::default(),
+            scope_anchor: None,
+            cfgs: Vec::new(),
+        }
+    }
+
+    fn group(module: &str, scope: &str, functions: Vec<RouteFunction>) -> ModuleGroup {
+        ModuleGroup {
+            module_path: vec![module.to_string()],
+            scope: scope.to_string(),
+            functions,
+            middlewares: Vec::new(),
+            providers: Vec::new(),
+            hooks: Vec::new(),
+        }
+    }
+
+    fn order(groups: &[ModuleGroup]) -> Vec<Vec<&str>> {
+        groups
+            .iter()
+            .map(|group| group.functions.iter().map(|f| f.name.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn sorts_handlers_within_groups() {
+        let mut groups = vec![group(
+            "users",
+            "/users",
+            vec![
+                function("get_user", "/{id}", 0),
+                function("list", "", 0),
+                function("new_user", "/new", 0),
+                function("export", "/{id}", 1),
+            ],
+        )];
+        sort_module_groups(&mut groups);
+        assert_eq!(order(&groups), [["export", "list", "new_user", "get_user"]]);
+    }
+
+    #[test]
+    fn sorts_groups_by_priority_then_scope() {
+        let mut groups = vec![
+            group("root", "", vec![function("index", "/", 0)]),
+            group("api", "/api", vec![function("api", "/", 0)]),
+            group("users", "/api/users", vec![function("users", "/", 0)]),
+            group("fallback", "", vec![function("fallback", "/{tail:.*}", 3)]),
+            group("admin", "/admin", vec![function("admin", "/", 0)]),
+        ];
+        sort_module_groups(&mut groups);
+        let modules: Vec<&str> = groups.iter().map(|g| g.module_path[0].as_str()).collect();
+        assert_eq!(modules, ["fallback", "users", "admin", "api", "root"]);
+    }
+}
@@ -0,0 +1,352 @@
+use crate::diagnostics::Diagnostics;
+use crate::file_summary::FileSummary;
+use crate::tools::stable_hash;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+/// 缓存格式的版本，FileSummary 的结构或提取规则变化时递增，旧缓存随之失效
+const CACHE_FORMAT: u32 = 6;
+
+/// 跨宏展开复用的文件解析结果
+///
+/// 每次展开 `generate_configure!` 都要读取并解析整个模块树，缓存以文件路径为键，
+/// 记录文件的修改时间、大小和内容哈希：修改时间和大小都没有变化时直接复用，
+/// 否则读取文件，内容哈希相同时同样复用，只有内容变化的文件才会重新解析。
+///
+/// 缓存文件位于 `OUT_DIR`（build.rs），或 `CARGO_TARGET_DIR` / 上级目录中的 `target` 下的
+/// `route_codegen` 目录；找不到时只在本次扫描中使用。
+#[derive(Default)]
+pub struct ScanCache {
+    path: Option<PathBuf>,                    // 缓存文件，None 表示不写入磁盘
+    entries: Mutex<BTreeMap<PathBuf, Entry>>, // 规范化的文件路径 -> 解析结果
+    changed: Mutex<bool>,                     // 是否有需要写回的变化
+    reused: AtomicUsize,                      // 复用的文件数
+    parsed: AtomicUsize,                      // 重新解析的文件数
+}
+
+/// 缓存文件的内容
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    version: String,
+    files: BTreeMap<PathBuf, Entry>,
+}
+
+/// 单个文件的解析结果及其对应的文件状态
+#[derive(Clone, Serialize, Deserialize)]
+struct Entry {
+    modified: (u64, u32), // 修改时间（秒、纳秒）
+    len: u64,             // 文件大小
+    hash: u64,            // 文件内容的哈希
+    summary: FileSummary, // 解析结果
+}
+
+impl ScanCache {
+    /// 只在本次扫描中使用的缓存
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// 打开 manifest_dir 对应的缓存文件，文件不存在、无法解析或版本不同时从空缓存开始
+    pub fn open(manifest_dir: &Path) -> Self {
+        match cache_file(manifest_dir) {
+            Some(path) => Self::load(path),
+            None => Self::in_memory(),
+        }
+    }
+
+    /// 读取指定的缓存文件
+    fn load(path: PathBuf) -> Self {
+        let entries = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<CacheFile>(&content).ok())
+            .filter(|cache| cache.version == version())
+            .map(|cache| cache.files)
+            .unwrap_or_default();
+        Self {
+            path: Some(path),
+            entries: Mutex::new(entries),
+            ..Self::default()
+        }
+    }
+
+    /// 读取并解析 .rs 文件，文件没有变化时返回缓存的结果
+    ///
+    /// 错误信息包含文件路径（解析失败时还包含行号和列号）；无法解析的文件不缓存。
+    pub fn summary(&self, path: &Path) -> Result<FileSummary, String> {
+        // 限制最大文件大小为10MB
+        const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+        let metadata = fs::metadata(path)
+            .map_err(|e| format!("{}: failed to get file metadata: {}", path.display(), e))?;
+        if metadata.len() > MAX_FILE_SIZE {
+            return Err(format!("{}: file size exceeds limit", path.display()));
+        }
+
+        let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|time| (time.as_secs(), time.subsec_nanos()))
+            .unwrap_or_default();
+        if let Some(entry) = self.entries.lock().unwrap().get(&key)
+            && entry.modified == modified
+            && entry.len == metadata.len()
+        {
+            self.reused.fetch_add(1, Ordering::Relaxed);
+            return Ok(entry.summary.clone());
+        }
+
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("{}: failed to read file: {}", path.display(), e))?;
+        let hash = content_hash(&content);
+        // 只有修改时间变化（如 touch、切换分支后又切回）时，更新记录的文件状态后复用
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(&key)
+            && entry.hash == hash
+        {
+            entry.modified = modified;
+            entry.len = metadata.len();
+            *self.changed.lock().unwrap() = true;
+            self.reused.fetch_add(1, Ordering::Relaxed);
+            return Ok(entry.summary.clone());
+        }
+
+        // 解析在锁外进行，模块树中的文件可以并行解析
+        let file = syn::parse_file(&content).map_err(|e| {
+            let start = e.span().start();
+            format!(
+                "{}:{}:{}: failed to parse file: {}",
+                path.display(),
+                start.line,
+                start.column + 1,
+                e
+            )
+        })?;
+        let summary = FileSummary::new(&file);
+        self.entries.lock().unwrap().insert(
+            key,
+            Entry {
+                modified,
+                len: metadata.len(),
+                hash,
+                summary: summary.clone(),
+            },
+        );
+        *self.changed.lock().unwrap() = true;
+        self.parsed.fetch_add(1, Ordering::Relaxed);
+        Ok(summary)
+    }
+
+    /// 把变化写回缓存文件，同时去掉已经不存在的文件
+    ///
+    /// 先写入临时文件再重命名，同时展开的其他宏（如 rust-analyzer）不会读到写了一半的文件。
+    pub fn save(&self, diagnostics: &Diagnostics) {
+        diagnostics.info(format!(
+            "🗄️ Scan cache: {} file(s) reused, {} parsed",
+            self.reused.load(Ordering::Relaxed),
+            self.parsed.load(Ordering::Relaxed)
+        ));
+        let Some(path) = &self.path else {
+            return;
+        };
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|file, _| file.is_file());
+        if !*self.changed.lock().unwrap() && entries.len() == before {
+            return;
+        }
+
+        let cache = CacheFile {
+            version: version(),
+            files: entries.clone(),
+        };
+        let written = serde_json::to_string(&cache)
+            .map_err(|e| e.to_string())
+            .and_then(|content| {
+                let dir = path.parent().unwrap_or(Path::new("."));
+                fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+                let temp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+                fs::write(&temp, content).map_err(|e| e.to_string())?;
+                fs::rename(&temp, path).map_err(|e| e.to_string())
+            });
+        if let Err(e) = written {
+            diagnostics.info(format!(
+                "⚠️ Failed to write scan cache {}: {}",
+                path.display(),
+                e
+            ));
+        }
+    }
+}
+
+/// 缓存的版本：缓存格式、route_codegen_build 的版本及其源码指纹（build.rs 计算）都相同时才能复用
+fn version() -> String {
+    format!(
+        "{}-{}-{}",
+        env!("CARGO_PKG_VERSION"),
+        CACHE_FORMAT,
+        env!("ROUTE_CODEGEN_BUILD_FINGERPRINT")
+    )
+}
+
+fn content_hash(content: &str) -> u64 {
+    stable_hash(content.as_bytes())
+}
+
+/// manifest_dir 对应的缓存文件
+///
+/// build.rs 中使用 `OUT_DIR`；否则使用 `CARGO_TARGET_DIR`，或向上查找 Cargo 创建的
+/// `target` 目录（包含 `CACHEDIR.TAG`），每个 crate 一个以目录哈希区分的文件。
+fn cache_file(manifest_dir: &Path) -> Option<PathBuf> {
+    if let Some(out_dir) = std::env::var_os("OUT_DIR") {
+        return Some(PathBuf::from(out_dir).join("route_codegen-scan.json"));
+    }
+    let target_dir = std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .or_else(|| {
+            manifest_dir
+                .ancestors()
+                .map(|dir| dir.join("target"))
+                .find(|target| target.join("CACHEDIR.TAG").is_file())
+        })?;
+
+    let manifest_dir = manifest_dir
+        .canonicalize()
+        .unwrap_or_else(|_| manifest_dir.to_path_buf());
+    let name = manifest_dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("crate");
+    let hash = stable_hash(manifest_dir.as_os_str().as_encoded_bytes());
+    Some(
+        target_dir
+            .join("route_codegen")
+            .join(format!("{}-{:016x}.json", name, hash)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+    use std::time::{Duration, SystemTime};
+
+    const ROUTE: &str = "#[get(\"/a\")] async fn a() {}";
+
+    fn counts(cache: &ScanCache) -> (usize, usize) {
+        (
+            cache.reused.load(Ordering::Relaxed),
+            cache.parsed.load(Ordering::Relaxed),
+        )
+    }
+
+    fn set_modified(path: &Path, time: SystemTime) {
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+
+    #[test]
+    fn reparses_changed_files() {
+        let dir = TestDir::new("scan_cache_changed");
+        let file = dir.write("src/lib.rs", "fn helper() {}");
+        let cache = ScanCache::in_memory();
+
+        assert!(!cache.summary(&file).unwrap().has_routes());
+        assert!(!cache.summary(&file).unwrap().has_routes());
+        assert_eq!(counts(&cache), (1, 1));
+
+        fs::write(&file, ROUTE).unwrap();
+        assert!(cache.summary(&file).unwrap().has_routes());
+        assert_eq!(counts(&cache), (1, 2));
+    }
+
+    #[test]
+    fn same_size_edit_is_detected_by_modified_time() {
+        let dir = TestDir::new("scan_cache_same_size");
+        let file = dir.write("src/lib.rs", "fn aaaaaa() {}");
+        let cache = ScanCache::in_memory();
+        cache.summary(&file).unwrap();
+
+        let modified = fs::metadata(&file).unwrap().modified().unwrap();
+        fs::write(&file, "fn bbbbbb() {}").unwrap();
+        set_modified(&file, modified + Duration::from_secs(1));
+        cache.summary(&file).unwrap();
+        assert_eq!(counts(&cache), (0, 2));
+    }
+
+    #[test]
+    fn touched_file_with_same_content_is_reused() {
+        let dir = TestDir::new("scan_cache_touched");
+        let file = dir.write("src/lib.rs", ROUTE);
+        let cache = ScanCache::in_memory();
+        cache.summary(&file).unwrap();
+
+        let modified = fs::metadata(&file).unwrap().modified().unwrap();
+        set_modified(&file, modified + Duration::from_secs(1));
+        assert!(cache.summary(&file).unwrap().has_routes());
+        assert_eq!(counts(&cache), (1, 1));
+    }
+
+    #[test]
+    fn unparsable_files_are_not_cached() {
+        let dir = TestDir::new("scan_cache_unparsable");
+        let file = dir.write("src/lib.rs", "fn broken( {}");
+        let cache = ScanCache::in_memory();
+
+        let Err(error) = cache.summary(&file) else {
+            panic!("{} should not parse", file.display());
+        };
+        assert!(error.contains("lib.rs:1:"), "{}", error);
+        assert!(cache.entries.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn saved_cache_is_reused_only_with_the_same_version() {
+        let dir = TestDir::new("scan_cache_saved");
+        let file = dir.write("src/lib.rs", ROUTE);
+        let cache_path = dir.path().join("cache/scan.json");
+        let diagnostics = Diagnostics::new().quiet(true);
+
+        let cache = ScanCache::load(cache_path.clone());
+        cache.summary(&file).unwrap();
+        cache.save(&diagnostics);
+
+        let reopened = ScanCache::load(cache_path.clone());
+        assert!(reopened.summary(&file).unwrap().has_routes());
+        assert_eq!(counts(&reopened), (1, 0));
+
+        // 缓存格式或 route_codegen_build 的源码变化后，旧的缓存不再使用
+        let content = fs::read_to_string(&cache_path).unwrap();
+        fs::write(&cache_path, content.replace(&version(), "0.0.0-0-0")).unwrap();
+        let outdated = ScanCache::load(cache_path);
+        outdated.summary(&file).unwrap();
+        assert_eq!(counts(&outdated), (0, 1));
+    }
+
+    #[test]
+    fn save_drops_deleted_files() {
+        let dir = TestDir::new("scan_cache_deleted");
+        let kept = dir.write("src/lib.rs", ROUTE);
+        let deleted = dir.write("src/old.rs", ROUTE);
+        let cache_path = dir.path().join("scan.json");
+        let diagnostics = Diagnostics::new().quiet(true);
+
+        let cache = ScanCache::load(cache_path.clone());
+        cache.summary(&kept).unwrap();
+        cache.summary(&deleted).unwrap();
+        fs::remove_file(&deleted).unwrap();
+        cache.save(&diagnostics);
+
+        let reopened = ScanCache::load(cache_path);
+        let files: Vec<PathBuf> = reopened.entries.lock().unwrap().keys().cloned().collect();
+        assert_eq!(files, [kept.canonicalize().unwrap()]);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::configure_builder::{CodegenOptions, ModuleGroup};
+use crate::middleware::{wrap_tokens, RouteMiddleware};
+use crate::provider::{app_data_tokens, RouteProvider};
+use quote::quote;
+use route_codegen_runtime::order::scope_segments;
+use syn::Ident;
+
+/// nested 模式下的 scope 树：每个节点对应前缀中的一段
+///
+/// 节点内的条目按分组的注册顺序排列，子树在其中第一个分组的位置整体注册，
+/// 这样 priority 和「长前缀优先」的排序在每一层都仍然成立。
+#[derive(Default)]
+struct ScopeNode {
+    segment: String,          // 前缀中的一段（根节点为空）
+    entries: Vec<ScopeEntry>, // 按注册顺序排列的分组和子 scope
+}
+
+enum ScopeEntry {
+    Group(usize),     // 直接注册在该节点上的分组（groups 中的下标）
+    Child(ScopeNode), // 子 scope
+}
+
+impl ScopeNode {
+    fn build(groups: &[ModuleGroup]) -> ScopeNode {
+        let mut root = ScopeNode::default();
+        for (index, group) in groups.iter().enumerate() {
+            let mut node = &mut root;
+            for segment in scope_segments(&group.scope) {
+                node = node.child(segment);
+            }
+            node.entries.push(ScopeEntry::Group(index));
+        }
+        root
+    }
+
+    /// 查找或创建子节点
+    fn child(&mut self, segment: &str) -> &mut ScopeNode {
+        let position = self.entries.iter().position(
+            |entry| matches!(entry, ScopeEntry::Child(child) if child.segment == segment),
+        );
+        let position = position.unwrap_or_else(|| {
+            self.entries.push(ScopeEntry::Child(ScopeNode {
+                segment: segment.to_string(),
+                entries: Vec::new(),
+            }));
+            self.entries.len() - 1
+        });
+        match &mut self.entries[position] {
+            ScopeEntry::Child(child) => child,
+            ScopeEntry::Group(_) => unreachable!(),
+        }
+    }
+
+    /// 深度优先的分组顺序，即 actix 实际尝试匹配的顺序
+    fn group_order(&self, order: &mut Vec<usize>) {
+        for entry in &self.entries {
+            match entry {
+                ScopeEntry::Group(index) => order.push(*index),
+                ScopeEntry::Child(child) => child.group_order(order),
+            }
+        }
+    }
+
+    /// 该节点需要的中间件和 provider：直接注册在节点上的分组所需、且上级节点尚未注册的
+    fn scoped_fns(&self, groups: &[ModuleGroup], applied: &ScopedFns) -> ScopedFns {
+        let mut scoped = ScopedFns::default();
+        for entry in &self.entries {
+            if let ScopeEntry::Group(index) = entry {
+                let group = &groups[*index];
+                for middleware in &group.middlewares {
+                    if !applied.middlewares.contains(middleware)
+                        && !scoped.middlewares.contains(middleware)
+                    {
+                        scoped.middlewares.push(middleware.clone());
+                    }
+                }
+                for provider in &group.providers {
+                    if !applied.providers.contains(provider) && !scoped.providers.contains(provider)
+                    {
+                        scoped.providers.push(provider.clone());
+                    }
+                }
+            }
+        }
+        scoped
+    }
+
+    /// 生成该节点内的注册语句，`applied` 为上级节点已经包装的中间件
+    fn to_tokens(
+        &self,
+        groups: &[ModuleGroup],
+        register_idents: &[Ident],
+        providers: &[RouteProvider],
+        options: &CodegenOptions,
+        applied: &ScopedFns,
+    ) -> proc_macro2::TokenStream {
+        let entries = self.entries.iter().map(|entry| match entry {
+            ScopeEntry::Group(index) => {
+                let register_ident = &register_idents[*index];
+                quote! {
+                    #register_ident(cfg);
+                }
+            }
+            ScopeEntry::Child(child) => {
+                let scope = format!("/{}", child.segment);
+                let scoped = child.scoped_fns(groups, applied);
+                let wraps = wrap_tokens(&scoped.middlewares, options);
+                let app_data = app_data_tokens(&scoped.providers, providers);
+                let applied = applied.extend(scoped);
+                let body = child.to_tokens(groups, register_idents, providers, options, &applied);
+                quote! {
+                    cfg.service(actix_web::web::scope(#scope)#app_data #wraps.configure(|cfg| {
+                        #body
+                    }));
+                }
+            }
+        });
+        quote! {
+            #(#entries)*
+        }
+    }
+}
+
+/// 按 scope 树的深度优先顺序重排分组，使路由表和冲突检查与实际的注册顺序一致
+pub fn sort_nested_groups(groups: &mut Vec<ModuleGroup>) {
+    let mut order = Vec::with_capacity(groups.len());
+    ScopeNode::build(groups).group_order(&mut order);
+
+    let mut slots: Vec<Option<ModuleGroup>> = groups.drain(..).map(Some).collect();
+    groups.extend(order.into_iter().filter_map(|index| slots[index].take()));
+}
+
+/// scope 树中一个节点上注册的中间件和 provider
+#[derive(Default)]
+struct ScopedFns {
+    middlewares: Vec<RouteMiddleware>,
+    providers: Vec<RouteProvider>,
+}
+
+impl ScopedFns {
+    fn extend(&self, other: ScopedFns) -> ScopedFns {
+        ScopedFns {
+            middlewares: [self.middlewares.clone(), other.middlewares].concat(),
+            providers: [self.providers.clone(), other.providers].concat(),
+        }
+    }
+}
+
+/// 生成嵌套的 scope：`scope("/a").configure(|cfg| { ...; cfg.service(scope("/b")...) })`
+///
+/// `register_idents` 与 `groups` 一一对应，分组中的 handler 由对应的 register 函数注册到所在的 scope 中。
+/// 中间件和 provider 注册在分组所在的 scope 上，因此也作用于其下所有的子 scope；
+/// 根上的 provider 直接注册在入口函数上，根上的分组需要中间件时，整棵树放在一个包装过的 `scope("")` 中。
+pub fn build_nested_scopes(
+    groups: &[ModuleGroup],
+    register_idents: &[Ident],
+    providers: &[RouteProvider],
+    options: &CodegenOptions,
+) -> proc_macro2::TokenStream {
+    let root = ScopeNode::build(groups);
+    let scoped = root.scoped_fns(groups, &ScopedFns::default());
+    let wraps = wrap_tokens(&scoped.middlewares, options);
+    let idents = crate::provider::provider_idents(&scoped.providers, providers);
+    let has_middlewares = !scoped.middlewares.is_empty();
+    let body = root.to_tokens(groups, register_idents, providers, options, &scoped);
+    let body = if has_middlewares {
+        quote! {
+            cfg.service(actix_web::web::scope("")#wraps.configure(|cfg| {
+                #body
+            }));
+        }
+    } else {
+        body
+    };
+    quote! {
+        #(cfg.app_data(#idents.clone());)*
+        #body
+    }
+}
@@ -0,0 +1,41 @@
+//! 单元测试使用的临时目录
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 系统临时目录下的测试目录，离开作用域时删除
+pub struct TestDir {
+    path: PathBuf,
+}
+
+impl TestDir {
+    /// 创建空的测试目录，`name` 在所有测试中唯一
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "route_codegen_test_{}_{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 写入文件（相对测试目录），自动创建上级目录
+    pub fn write(&self, file: &str, content: &str) -> PathBuf {
+        let path = self.path.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
@@ -0,0 +1,113 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn is_rust_keyword(s: &str) -> bool {
+    matches!(
+        s,
+        "as" | "break"
+            | "const"
+            | "continue"
+            | "else"
+            | "enum"
+            | "extern"
+            | "false"
+            | "fn"
+            | "for"
+            | "if"
+            | "impl"
+            | "in"
+            | "let"
+            | "loop"
+            | "match"
+            | "mod"
+            | "move"
+            | "mut"
+            | "pub"
+            | "ref"
+            | "return"
+            | "self"
+            | "Self"
+            | "static"
+            | "struct"
+            | "super"
+            | "trait"
+            | "true"
+            | "type"
+            | "unsafe"
+            | "use"
+            | "where"
+            | "while"
+            | "async"
+            | "await"
+            | "dyn"
+            | "abstract"
+            | "become"
+            | "box"
+            | "do"
+            | "final"
+            | "macro"
+            | "override"
+            | "priv"
+            | "typeof"
+            | "unsized"
+            | "virtual"
+            | "yield"
+            | "try"
+    )
+}
+
+/// 64 位 FNV-1a 哈希
+///
+/// 与 `DefaultHasher` 不同，结果不随 Rust 版本或进程变化，可以写入缓存文件和生成的标识符。
+pub fn stable_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// 递归收集目录下的 .rs 文件，按路径排序；`skip` 中的目录（如 src/bin）不进入
+pub fn collect_rs_files(dir: &Path, skip: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    collect_rs_files_into(dir, skip, &mut files);
+    files.sort();
+    files
+}
+
+fn collect_rs_files_into(dir: &Path, skip: &[PathBuf], result: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            if !skip.contains(&path) {
+                collect_rs_files_into(&path, skip, result);
+            }
+        } else if path.extension().and_then(|e| e.to_str()) == Some("rs") {
+            result.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    #[test]
+    fn collects_rs_files_except_skipped_dirs() {
+        let dir = TestDir::new("collect_rs_files");
+        dir.write("src/main.rs", "");
+        dir.write("src/bin/tool.rs", "");
+        dir.write("src/api/bin/mod.rs", "");
+        dir.write("src/api/notes.md", "");
+
+        let src = dir.path().join("src");
+        let files: Vec<PathBuf> = collect_rs_files(&src, &[src.join("bin")])
+            .iter()
+            .map(|file| file.strip_prefix(&src).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(files, [Path::new("api/bin/mod.rs"), Path::new("main.rs")]);
+        assert_eq!(collect_rs_files(&src, &[]).len(), 3);
+    }
+}
@@ -0,0 +1,280 @@
+use crate::configure_builder::module_segments;
+use crate::provider::type_key;
+use quote::quote;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use syn::ext::IdentExt;
+use syn::visit_mut::{self, VisitMut};
+use syn::{Item, Type, TypePath, UseTree};
+
+/// 不需要导入即可使用的类型名称（基本类型和常用的 prelude 类型）
+const PRELUDE: &[&str] = &[
+    "bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64",
+    "i128", "isize", "f32", "f64", "String", "Vec", "Option", "Result", "Box",
+];
+
+/// 解析 `use` 链的最大深度，避免循环的重导出
+const MAX_REEXPORTS: usize = 8;
+
+/// 模块中可以直接引用的名称：`use` 声明引入的名称，以及模块中定义的类型和子模块
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ModuleNames {
+    pub imports: BTreeMap<String, Vec<String>>, // 引入的名称 -> 书写的路径（如 AppState -> crate::state::AppState）
+    pub items: Vec<String>, // 模块中定义的结构体、枚举、类型别名、trait 和子模块
+}
+
+impl ModuleNames {
+    pub fn new(items: &[Item]) -> Self {
+        let mut names = ModuleNames::default();
+        for item in items {
+            let ident = match item {
+                Item::Use(item_use) => {
+                    names.collect_imports(&item_use.tree, Vec::new());
+                    continue;
+                }
+                Item::Struct(item) => &item.ident,
+                Item::Enum(item) => &item.ident,
+                Item::Union(item) => &item.ident,
+                Item::Type(item) => &item.ident,
+                Item::Trait(item) => &item.ident,
+                Item::Mod(item) => &item.ident,
+                _ => continue,
+            };
+            names.items.push(ident.unraw().to_string());
+        }
+        names
+    }
+
+    /// 展开 use 树，glob 导入（`use x::*`）和 `as _` 不引入可以解析的名称
+    fn collect_imports(&mut self, tree: &UseTree, prefix: Vec<String>) {
+        match tree {
+            UseTree::Path(path) => {
+                let mut prefix = prefix;
+                prefix.push(path.ident.unraw().to_string());
+                self.collect_imports(&path.tree, prefix);
+            }
+            UseTree::Name(name) if name.ident == "self" => {
+                if let Some(last) = prefix.last() {
+                    self.imports.insert(last.clone(), prefix);
+                }
+            }
+            UseTree::Name(name) => {
+                let name = name.ident.unraw().to_string();
+                let mut path = prefix;
+                path.push(name.clone());
+                self.imports.insert(name, path);
+            }
+            UseTree::Rename(rename) if rename.rename == "_" => {}
+            UseTree::Rename(rename) => {
+                let mut path = prefix;
+                if rename.ident != "self" {
+                    path.push(rename.ident.unraw().to_string());
+                }
+                self.imports.insert(rename.rename.unraw().to_string(), path);
+            }
+            UseTree::Glob(_) => {}
+            UseTree::Group(group) => {
+                for tree in &group.items {
+                    self.collect_imports(tree, prefix.clone());
+                }
+            }
+        }
+    }
+}
+
+/// 所在模块的完整路径（如 crate::handler::agency、api_tool::api）
+pub fn module_path(crate_root: &str, module_prefix: &str) -> Vec<String> {
+    let segments = module_segments(module_prefix);
+    if crate_root == "crate" {
+        [vec![crate_root.to_string()], segments].concat()
+    } else {
+        segments
+    }
+}
+
+/// 类型的比较键：能够解析时为完整路径，否则只有去掉路径前缀的名称
+#[derive(Clone, Debug)]
+pub struct TypeKey {
+    pub name: String,         // 去掉路径前缀的名称（如 AppState），用于诊断信息
+    pub path: Option<String>, // 完整路径（如 crate::state::AppState）
+}
+
+impl TypeKey {
+    /// 两边都解析出完整路径时比较完整路径，否则比较名称
+    pub fn matches(&self, other: &TypeKey) -> bool {
+        match (&self.path, &other.path) {
+            (Some(a), Some(b)) => a == b,
+            _ => self.name == other.name,
+        }
+    }
+}
+
+/// 按扫描到的模块中的 `use` 声明和条目，把类型中的路径解析为完整路径
+///
+/// 重导出（`pub use state::AppState`）会被跟随到定义类型的模块；
+/// glob 导入、宏生成的条目等无法确定的名称不解析，比较时退回到名称。
+pub struct TypeResolver<'a> {
+    modules: &'a BTreeMap<String, ModuleNames>, // 模块完整路径 -> 模块中的名称
+}
+
+impl<'a> TypeResolver<'a> {
+    pub fn new(modules: &'a BTreeMap<String, ModuleNames>) -> Self {
+        Self { modules }
+    }
+
+    /// 模块 `module` 中书写的类型 `ty` 的比较键
+    pub fn key(&self, ty: &str, module: &[String]) -> TypeKey {
+        let Ok(ty) = syn::parse_str::<Type>(ty) else {
+            return TypeKey {
+                name: ty.to_string(),
+                path: None,
+            };
+        };
+        TypeKey {
+            name: type_key(&ty),
+            path: self.resolve(&ty, module),
+        }
+    }
+
+    /// 模块 `module` 中书写的条目路径（如 models::User）解析出的完整路径
+    pub fn item_path(&self, path: &str, module: &[String]) -> Option<String> {
+        let segments: Vec<String> = path.split("::").map(str::to_string).collect();
+        self.resolve_path(&segments, module)
+            .map(|resolved| resolved.join("::"))
+    }
+
+    /// 解析类型中（包括泛型参数中）的所有路径，任何一个无法解析时返回 None
+    fn resolve(&self, ty: &Type, module: &[String]) -> Option<String> {
+        let mut ty = ty.clone();
+        let mut visitor = ResolveVisitor {
+            resolver: self,
+            module,
+            resolved: true,
+        };
+        visitor.visit_type_mut(&mut ty);
+        visitor.resolved.then(|| {
+            quote!(#ty)
+                .to_string()
+                .split_whitespace()
+                .collect::<String>()
+        })
+    }
+
+    /// 解析模块 `module` 中书写的路径
+    fn resolve_path(&self, segments: &[String], module: &[String]) -> Option<Vec<String>> {
+        let names = self.modules.get(&module.join("::"));
+        let (first, rest) = segments.split_first()?;
+        let path = match names.and_then(|names| names.imports.get(first)) {
+            Some(import) => anchor([import.as_slice(), rest].concat(), module, names)?,
+            None if rest.is_empty() && !names.is_some_and(|names| names.items.contains(first)) => {
+                return PRELUDE.contains(&first.as_str()).then(|| segments.to_vec());
+            }
+            None => anchor(segments.to_vec(), module, names)?,
+        };
+        Some(self.follow_reexports(path))
+    }
+
+    /// 路径指向的模块重导出了该名称时，继续解析到定义它的模块
+    fn follow_reexports(&self, mut path: Vec<String>) -> Vec<String> {
+        for _ in 0..MAX_REEXPORTS {
+            let Some((name, module)) = path.split_last() else {
+                break;
+            };
+            let Some(names) = self.modules.get(&module.join("::")) else {
+                break;
+            };
+            if names.items.contains(name) {
+                break;
+            }
+            match names
+                .imports
+                .get(name)
+                .and_then(|import| anchor(import.clone(), module, Some(names)))
+            {
+                Some(next) if next != path => path = next,
+                _ => break,
+            }
+        }
+        path
+    }
+}
+
+/// 解析路径开头的 `crate`、`self`、`super` 和模块中定义的名称，其余路径以外部 crate 名开头
+fn anchor(
+    segments: Vec<String>,
+    module: &[String],
+    names: Option<&ModuleNames>,
+) -> Option<Vec<String>> {
+    let first = segments.first()?;
+    if first == "crate" {
+        return Some([module.get(..1)?, &segments[1..]].concat());
+    }
+    if first == "self" || first == "super" {
+        let mut base = module.to_vec();
+        let mut rest = &segments[..];
+        if rest.first().is_some_and(|s| s == "self") {
+            rest = &rest[1..];
+        }
+        while rest.first().is_some_and(|s| s == "super") {
+            if base.len() <= 1 {
+                return None;
+            }
+            base.pop();
+            rest = &rest[1..];
+        }
+        return Some([base.as_slice(), rest].concat());
+    }
+    if names.is_some_and(|names| names.items.contains(first)) {
+        return Some([module, &segments].concat());
+    }
+    Some(segments)
+}
+
+/// 把类型中的每个路径替换为完整路径
+struct ResolveVisitor<'r, 'a> {
+    resolver: &'r TypeResolver<'a>,
+    module: &'r [String],
+    resolved: bool, // 所有路径都已解析
+}
+
+impl VisitMut for ResolveVisitor<'_, '_> {
+    fn visit_type_path_mut(&mut self, type_path: &mut TypePath) {
+        visit_mut::visit_type_path_mut(self, type_path);
+        if type_path.qself.is_some() {
+            self.resolved = false;
+            return;
+        }
+        let segments: Vec<String> = type_path
+            .path
+            .segments
+            .iter()
+            .map(|segment| segment.ident.unraw().to_string())
+            .collect();
+        let resolved = if type_path.path.leading_colon.is_some() {
+            Some(segments)
+        } else {
+            self.resolver.resolve_path(&segments, self.module)
+        };
+        let Some(resolved) = resolved else {
+            self.resolved = false;
+            return;
+        };
+
+        // 保留最后一段上的泛型参数
+        let arguments = type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.arguments.clone())
+            .unwrap_or_default();
+        let span = proc_macro2::Span::call_site();
+        type_path.path.leading_colon = None;
+        type_path.path.segments = resolved
+            .iter()
+            .map(|segment| syn::PathSegment::from(syn::Ident::new(segment, span)))
+            .collect();
+        if let Some(last) = type_path.path.segments.last_mut() {
+            last.arguments = arguments;
+        }
+    }
+}
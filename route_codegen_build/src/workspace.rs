@@ -0,0 +1,317 @@
+use crate::dependency_tracker::DependencyTracker;
+use crate::diagnostics::Diagnostics;
+use crate::read_cargo_toml;
+use globset::Glob;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::Value;
+
+/// Cargo.toml 中的 `[workspace]` 配置
+#[derive(Debug, Default)]
+pub struct WorkspaceConfig {
+    members: Vec<String>,         // 成员路径，支持 glob（如 crates/*）
+    exclude: Vec<String>,         // 排除的路径
+    default_members: Vec<String>, // 默认构建的成员，必须同时是成员；非空时只扫描它们
+}
+
+/// 读取并解析当前项目的 Cargo.toml，提取其中的 workspace 配置
+///
+/// 没有 `[workspace]` 时返回 `Ok(None)`；文件无法解析或字段格式错误时返回错误。
+pub fn read_workspace_config(manifest_dir: &Path) -> Result<Option<WorkspaceConfig>, String> {
+    let path = manifest_dir.join("Cargo.toml");
+    let cargo_toml = read_cargo_toml(&path)?;
+    let Some(workspace) = cargo_toml.get("workspace") else {
+        return Ok(None);
+    };
+    Ok(Some(WorkspaceConfig {
+        members: string_array(workspace, "members", &path)?,
+        exclude: string_array(workspace, "exclude", &path)?,
+        default_members: string_array(workspace, "default-members", &path)?,
+    }))
+}
+
+/// 读取 `workspace.<key>` 字符串数组，不存在时为空
+fn string_array(workspace: &Value, key: &str, path: &Path) -> Result<Vec<String>, String> {
+    let Some(value) = workspace.get(key) else {
+        return Ok(Vec::new());
+    };
+    let Value::Array(entries) = value else {
+        return Err(format!(
+            "{}: `workspace.{}` must be an array",
+            path.display(),
+            key
+        ));
+    };
+    entries
+        .iter()
+        .map(|entry| {
+            entry.as_str().map(|s| s.to_string()).ok_or_else(|| {
+                format!(
+                    "{}: `workspace.{}` entries must be strings, found `{}`",
+                    path.display(),
+                    key,
+                    entry
+                )
+            })
+        })
+        .collect()
+}
+
+impl WorkspaceConfig {
+    /// 按 Cargo 的规则解析要扫描的成员目录：展开 glob，去掉 `exclude` 中的路径和 workspace 根自身
+    ///
+    /// 与在虚拟 workspace 根运行 `cargo build` 一样，设置了 `default-members` 时只扫描默认成员，
+    /// 其中不属于成员的条目报告为问题。glob 展开的起始目录登记为依赖，新增的成员目录也会被发现。
+    pub fn member_dirs(
+        &self,
+        workspace_dir: &Path,
+        tracker: &DependencyTracker,
+        diagnostics: &Diagnostics,
+    ) -> Vec<PathBuf> {
+        let excluded: Vec<PathBuf> = self
+            .exclude
+            .iter()
+            .map(|path| normalize(&workspace_dir.join(path)))
+            .collect();
+        let root = normalize(workspace_dir);
+
+        let mut dirs = BTreeSet::new();
+        for member in &self.members {
+            if is_glob(member) {
+                // glob 匹配到的目录中没有 Cargo.toml 时不作为成员
+                for dir in expand_glob(workspace_dir, member, tracker) {
+                    if dir.join("Cargo.toml").is_file() {
+                        dirs.insert(normalize(&dir));
+                    }
+                }
+            } else {
+                let dir = workspace_dir.join(member);
+                if dir.join("Cargo.toml").is_file() {
+                    dirs.insert(normalize(&dir));
+                } else {
+                    diagnostics.problem(format!(
+                        "workspace member `{}` could not be resolved: {} does not exist",
+                        member,
+                        dir.join("Cargo.toml").display()
+                    ));
+                }
+            }
+        }
+        dirs.retain(|dir| *dir != root && !excluded.iter().any(|e| dir.starts_with(e)));
+
+        if !self.default_members.is_empty() {
+            let mut defaults = BTreeSet::new();
+            for member in &self.default_members {
+                let dir = normalize(&workspace_dir.join(member));
+                if dirs.contains(&dir) {
+                    defaults.insert(dir);
+                } else {
+                    diagnostics.problem(format!(
+                        "workspace default member `{}` is not a workspace member",
+                        member
+                    ));
+                }
+            }
+            dirs = defaults;
+        }
+        // 保持相对 workspace 根的路径形式，与扫描结果中的源码路径一致
+        dirs.into_iter()
+            .map(|dir| match dir.strip_prefix(&root) {
+                Ok(relative) => workspace_dir.join(relative),
+                Err(_) => dir,
+            })
+            .collect()
+    }
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+/// 逐段展开目录 glob，`**` 匹配任意层目录；第一个 glob 段之前的目录登记为依赖
+fn expand_glob(base: &Path, pattern: &str, tracker: &DependencyTracker) -> Vec<PathBuf> {
+    let components: Vec<&str> = pattern
+        .split(['/', '\\'])
+        .filter(|c| !c.is_empty() && *c != ".")
+        .collect();
+    let literal = components.iter().take_while(|c| !is_glob(c));
+    tracker.track_dir(literal.fold(base.to_path_buf(), |dir, c| dir.join(c)));
+    let mut result = Vec::new();
+    expand_components(base.to_path_buf(), &components, &mut result);
+    result.sort();
+    result.dedup();
+    result
+}
+
+fn expand_components(dir: PathBuf, components: &[&str], result: &mut Vec<PathBuf>) {
+    let Some((first, rest)) = components.split_first() else {
+        result.push(dir);
+        return;
+    };
+    if *first == "**" {
+        // 匹配零层，或进入任意子目录后继续匹配 **
+        expand_components(dir.clone(), rest, result);
+        for child in sub_dirs(&dir) {
+            expand_components(child, components, result);
+        }
+        return;
+    }
+    if !is_glob(first) {
+        let child = dir.join(first);
+        if child.is_dir() {
+            expand_components(child, rest, result);
+        }
+        return;
+    }
+    let Ok(glob) = Glob::new(first) else {
+        return;
+    };
+    let matcher = glob.compile_matcher();
+    for child in sub_dirs(&dir) {
+        if child.file_name().is_some_and(|name| matcher.is_match(name)) {
+            expand_components(child, rest, result);
+        }
+    }
+}
+
+/// 目录下的子目录（跳过隐藏目录和 target）
+fn sub_dirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_dir()
+                && path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|name| !name.starts_with('.') && name != "target")
+        })
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// 包含 manifest_dir 的 workspace 的根目录（manifest_dir 自身也可以是根），不在 workspace 中时为 None
+pub fn workspace_root(manifest_dir: &Path) -> Option<PathBuf> {
+    normalize(manifest_dir)
+        .ancestors()
+        .find(|dir| matches!(read_workspace_config(dir), Ok(Some(_))))
+        .map(Path::to_path_buf)
+}
+
+/// 规范化路径用于比较，无法规范化时原样返回
+pub fn normalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    const PACKAGE: &str = "[package]\nname = \"member\"\n";
+
+    /// 路径相对测试目录的形式
+    fn relative(dir: &TestDir, paths: &[PathBuf]) -> Vec<String> {
+        let base = normalize(dir.path());
+        paths
+            .iter()
+            .map(|path| {
+                normalize(path)
+                    .strip_prefix(&base)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    /// 以 workspace 根为基准的成员目录
+    fn members(dir: &TestDir, diagnostics: &Diagnostics) -> Vec<String> {
+        members_tracked(dir, &DependencyTracker::new(), diagnostics)
+    }
+
+    fn members_tracked(
+        dir: &TestDir,
+        tracker: &DependencyTracker,
+        diagnostics: &Diagnostics,
+    ) -> Vec<String> {
+        let workspace = read_workspace_config(dir.path()).unwrap().unwrap();
+        relative(
+            dir,
+            &workspace.member_dirs(dir.path(), tracker, diagnostics),
+        )
+    }
+
+    #[test]
+    fn expands_globs_and_applies_exclude() {
+        let dir = TestDir::new("workspace_glob");
+        dir.write(
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\", \"tools/**\", \"app\"]\nexclude = [\"crates/legacy\"]\n",
+        );
+        dir.write("app/Cargo.toml", PACKAGE);
+        dir.write("crates/a/Cargo.toml", PACKAGE);
+        dir.write("crates/legacy/Cargo.toml", PACKAGE);
+        dir.write("crates/docs/README.md", "");
+        dir.write("tools/gen/cli/Cargo.toml", PACKAGE);
+        dir.write("tools/target/Cargo.toml", PACKAGE);
+
+        let tracker = DependencyTracker::new();
+        let diagnostics = Diagnostics::new();
+        assert_eq!(
+            members_tracked(&dir, &tracker, &diagnostics),
+            ["app", "crates/a", "tools/gen/cli"]
+        );
+        assert!(diagnostics.warnings().is_empty());
+        // 新增的成员目录出现在 glob 展开的起始目录中
+        assert_eq!(relative(&dir, &tracker.dirs()), ["crates", "tools"]);
+    }
+
+    #[test]
+    fn reports_missing_member() {
+        let dir = TestDir::new("workspace_missing");
+        dir.write("Cargo.toml", "[workspace]\nmembers = [\"app\", \"gone\"]\n");
+        dir.write("app/Cargo.toml", PACKAGE);
+
+        let diagnostics = Diagnostics::new();
+        assert_eq!(members(&dir, &diagnostics), ["app"]);
+        assert_eq!(diagnostics.warnings().len(), 1);
+        assert!(diagnostics.warnings()[0].contains("`gone`"));
+    }
+
+    #[test]
+    fn scans_only_default_members() {
+        let dir = TestDir::new("workspace_default");
+        dir.write(
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\ndefault-members = [\"crates/b\", \"other\"]\n",
+        );
+        dir.write("crates/a/Cargo.toml", PACKAGE);
+        dir.write("crates/b/Cargo.toml", PACKAGE);
+
+        let diagnostics = Diagnostics::new();
+        assert_eq!(members(&dir, &diagnostics), ["crates/b"]);
+        assert_eq!(
+            diagnostics.warnings(),
+            ["workspace default member `other` is not a workspace member"]
+        );
+    }
+
+    #[test]
+    fn finds_enclosing_workspace_root() {
+        let dir = TestDir::new("workspace_root");
+        dir.write("Cargo.toml", "[workspace]\nmembers = [\"app\"]\n");
+        dir.write("app/Cargo.toml", PACKAGE);
+
+        assert_eq!(workspace_root(dir.path()), Some(normalize(dir.path())));
+        assert_eq!(
+            workspace_root(&dir.path().join("app")),
+            Some(normalize(dir.path()))
+        );
+    }
+}
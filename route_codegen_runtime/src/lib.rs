@@ -0,0 +1,55 @@
+pub mod openapi;
+pub mod order;
+#[cfg(feature = "registry")]
+pub mod registry;
+pub mod scope;
+
+/// 一条已注册路由的描述信息
+///
+/// `generate_configure!` 会同时生成 `pub static ROUTES: &[RouteInfo]`，按注册顺序列出所有路由，
+/// 启动工具、测试和管理接口可以直接读取它，而不必解析启动日志。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteInfo {
+    pub method: &'static str,                 // HTTP 方法（如 GET）
+    pub path: &'static str, // 完整路径模板（如 /handler/agency/agency_api/agency/{id}）
+    pub handler: &'static str, // handler 的 Rust 路径
+    pub crate_name: &'static str, // handler 所属的 crate
+    pub module: &'static str, // handler 所在模块（相对 crate 根）
+    pub file: &'static str, // 源码文件（相对调用宏的 crate 目录，其他 crate 的文件相对 workspace 根）
+    pub line: u32,          // 源码行号
+    pub path_params: &'static [&'static str], // 路径参数（如 ["id"]）
+}
+
+/// 解析路径模板中的参数名，如 /a/{id}/{tail:.*} -> ["id", "tail"]
+pub fn path_params(path: &str) -> Vec<String> {
+    let mut params = Vec::new();
+    let mut depth = 0;
+    let mut current = String::new();
+
+    for c in path.chars() {
+        match c {
+            '{' => {
+                if depth > 0 {
+                    current.push(c);
+                }
+                depth += 1;
+            }
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    let name = current.split(':').next().unwrap_or("").trim().to_string();
+                    if !name.is_empty() {
+                        params.push(name);
+                    }
+                    current.clear();
+                } else {
+                    current.push(c);
+                }
+            }
+            _ if depth > 0 => current.push(c),
+            _ => {}
+        }
+    }
+
+    params
+}
@@ -0,0 +1,102 @@
+//! 运行时拼接 OpenAPI 文档
+//!
+//! 生成的 `openapi_json()` 中每个 operation 是一段预先生成的 JSON，带有与 handler 相同的 `#[cfg]` 条件，
+//! 编译时被排除的 handler 不会出现在文档中，只被它们引用的 schema 也随之省略。
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// 一个 operation（路径 + 方法）的 JSON 片段
+#[derive(Debug, Clone, Copy)]
+pub struct Operation {
+    pub path: &'static str,   // 路径的 JSON 字符串（含引号，如 "\"/users/{id}\""）
+    pub method: &'static str, // 小写的 HTTP 方法（如 get）
+    pub json: &'static str,   // operation 对象
+    pub schemas: &'static [&'static str], // 引用的 components.schemas 名称的 JSON 字符串（含间接引用）
+}
+
+/// 按 components.schemas 中的名称排列的 schema：名称的 JSON 字符串和 schema 对象
+pub type Schema = (&'static str, &'static str);
+
+/// 拼接 OpenAPI 文档，`info` 为 info 对象的 JSON
+pub fn document(info: &str, operations: &[Operation], schemas: &[Schema]) -> String {
+    let mut paths: BTreeMap<&str, Vec<&Operation>> = BTreeMap::new();
+    let mut referenced = BTreeSet::new();
+    for operation in operations {
+        paths.entry(operation.path).or_default().push(operation);
+        referenced.extend(operation.schemas.iter().copied());
+    }
+
+    let paths = paths
+        .iter()
+        .map(|(path, operations)| {
+            let methods = operations
+                .iter()
+                .map(|operation| format!("\"{}\":{}", operation.method, operation.json))
+                .collect::<Vec<_>>()
+                .join(",");
+            format!("{}:{{{}}}", path, methods)
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let mut document = format!(
+        "{{\"openapi\":\"3.1.0\",\"info\":{},\"paths\":{{{}}}",
+        info, paths
+    );
+    let schemas = schemas
+        .iter()
+        .filter(|(name, _)| referenced.contains(name))
+        .map(|(name, schema)| format!("{}:{}", name, schema))
+        .collect::<Vec<_>>();
+    if !schemas.is_empty() {
+        document.push_str(&format!(
+            ",\"components\":{{\"schemas\":{{{}}}}}",
+            schemas.join(",")
+        ));
+    }
+    document.push('}');
+    document
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INFO: &str = r#"{"title":"demo","version":"0.1.0"}"#;
+
+    #[test]
+    fn groups_operations_by_path() {
+        let operations = [
+            Operation {
+                path: r#""/users""#,
+                method: "get",
+                json: r#"{"operationId":"list"}"#,
+                schemas: &[],
+            },
+            Operation {
+                path: r#""/users""#,
+                method: "post",
+                json: r#"{"operationId":"create"}"#,
+                schemas: &[],
+            },
+        ];
+        assert_eq!(
+            document(INFO, &operations, &[]),
+            r#"{"openapi":"3.1.0","info":{"title":"demo","version":"0.1.0"},"paths":{"/users":{"get":{"operationId":"list"},"post":{"operationId":"create"}}}}"#
+        );
+    }
+
+    #[test]
+    fn omits_schemas_of_excluded_operations() {
+        let operations = [Operation {
+            path: r#""/users""#,
+            method: "post",
+            json: r#"{"operationId":"create"}"#,
+            schemas: &[r#""User""#],
+        }];
+        let schemas = [(r#""Beta""#, "{}"), (r#""User""#, r#"{"type":"object"}"#)];
+        let document = document(INFO, &operations, &schemas);
+        assert!(document.ends_with(r#","components":{"schemas":{"User":{"type":"object"}}}}"#));
+        assert!(!document.contains("Beta"));
+    }
+}
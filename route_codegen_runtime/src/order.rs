@@ -0,0 +1,126 @@
+//! 路由的注册顺序
+//!
+//! actix 按注册顺序匹配 scope 和路由，排序策略为：
+//! - `#[route_codegen::priority(n)]` 数值大的先注册（模块取组内最大值）；
+//! - 前缀更长的 scope 先于更短的 scope，根 scope 最后注册；
+//! - 同一 scope 内，静态段先于动态段，更长的路径先于更短的路径；
+//! - 其余情况按名称排序。
+
+use std::cmp::Ordering;
+
+/// 同一 scope 内决定 handler 注册顺序的信息
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandlerOrder<'a> {
+    pub priority: i32, // 注册优先级
+    pub path: &'a str, // handler 的第一个路由路径
+    pub name: &'a str, // handler 名称
+}
+
+/// 同一 scope 内：priority 大的在前，然后按路径的具体程度，最后按名称
+pub fn compare_handlers(a: &HandlerOrder, b: &HandlerOrder) -> Ordering {
+    b.priority
+        .cmp(&a.priority)
+        .then_with(|| compare_paths(a.path, b.path))
+        .then_with(|| a.name.cmp(b.name))
+}
+
+/// 段数多（前缀更长）的 scope 在前，根 scope 自然排在最后
+pub fn compare_scopes(a: &str, b: &str) -> Ordering {
+    let a_segments = scope_segments(a);
+    let b_segments = scope_segments(b);
+    b_segments
+        .len()
+        .cmp(&a_segments.len())
+        .then_with(|| a_segments.cmp(&b_segments))
+}
+
+/// scope 前缀的非空段（根 scope 为空）
+pub fn scope_segments(scope: &str) -> Vec<&str> {
+    scope.split('/').filter(|s| !s.is_empty()).collect()
+}
+
+/// 逐段比较路径：静态段先于动态段，段数多的在前，最后按字典序
+pub fn compare_paths(a: &str, b: &str) -> Ordering {
+    let a_segments = path_segments(a);
+    let b_segments = path_segments(b);
+
+    for (x, y) in a_segments.iter().zip(&b_segments) {
+        match (is_dynamic(x), is_dynamic(y)) {
+            (false, true) => return Ordering::Less,
+            (true, false) => return Ordering::Greater,
+            _ => {}
+        }
+    }
+
+    b_segments
+        .len()
+        .cmp(&a_segments.len())
+        .then_with(|| a.cmp(b))
+}
+
+/// 拆分路径段（保留末尾的空段，actix 中 /a 与 /a/ 是不同的路由）
+pub fn path_segments(path: &str) -> Vec<&str> {
+    path.trim_start_matches('/').split('/').collect()
+}
+
+/// 判断是否是动态段（如 {id}）
+pub fn is_dynamic(segment: &str) -> bool {
+    segment.contains('{')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(mut items: Vec<&str>, compare: fn(&str, &str) -> Ordering) -> Vec<&str> {
+        items.sort_by(|a, b| compare(a, b));
+        items
+    }
+
+    #[test]
+    fn static_segments_before_dynamic() {
+        assert_eq!(
+            sorted(
+                vec!["/users/{id}", "/users/new", "/{page}", "/users"],
+                compare_paths
+            ),
+            ["/users/new", "/users/{id}", "/users", "/{page}"]
+        );
+    }
+
+    #[test]
+    fn longer_paths_first_then_lexical() {
+        assert_eq!(
+            sorted(vec!["/a", "/a/b/c", "/a/b", "/a/"], compare_paths),
+            ["/a/b/c", "/a/", "/a/b", "/a"]
+        );
+        assert_eq!(compare_paths("/a/x", "/a/y"), Ordering::Less);
+    }
+
+    #[test]
+    fn longer_scopes_first_and_root_last() {
+        assert_eq!(
+            sorted(vec!["", "/api", "/api/users", "/admin"], compare_scopes),
+            ["/api/users", "/admin", "/api", ""]
+        );
+        assert_eq!(compare_scopes("/api/", "api"), Ordering::Equal);
+    }
+
+    #[test]
+    fn priority_overrides_path_order() {
+        let order = |priority, path, name| HandlerOrder {
+            priority,
+            path,
+            name,
+        };
+        let mut handlers = [
+            order(0, "/users/{id}", "get_user"),
+            order(0, "/users/new", "new_user"),
+            order(5, "/{page}", "fallback"),
+            order(0, "/users/new", "another"),
+        ];
+        handlers.sort_by(compare_handlers);
+        let names: Vec<&str> = handlers.iter().map(|h| h.name).collect();
+        assert_eq!(names, ["fallback", "another", "new_user", "get_user"]);
+    }
+}
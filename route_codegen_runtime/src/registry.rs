@@ -0,0 +1,307 @@
+//! 静态注册：handler 通过 `#[route_codegen::register]` 在链接时登记自身，
+//! `generate_configure!(registration = "static")` 生成的 configure 在启动时遍历登记的 handler
+//!
+//! 登记基于 [`inventory`]：每个 handler 生成一个 [`StaticRoute`]，程序启动时在 `main` 之前被收集。
+//! 支持的目标与 inventory 相同（Linux、Android、BSD、illumos、Haiku、macOS、iOS、Windows、wasm 和裸机），
+//! 在其他目标上使用静态注册会报告编译错误（见 [`assert_supported_target!`]）。
+
+use crate::order::{compare_handlers, compare_scopes, HandlerOrder};
+use crate::scope::ScopeRules;
+use crate::{path_params, RouteInfo};
+use actix_web::web::{self, ServiceConfig};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+#[doc(hidden)]
+pub use inventory;
+
+/// 一个通过 `#[route_codegen::register]` 登记的 handler
+#[derive(Debug, Clone, Copy)]
+pub struct StaticRoute {
+    pub name: &'static str,                   // handler 名称
+    pub module_path: &'static str,            // 所在模块（module_path!()，以 crate 的库名开头）
+    pub file: &'static str,                   // 源码文件（file!()）
+    pub line: u32,                            // handler 所在行号
+    pub priority: i32,                        // 注册优先级（#[route_codegen::priority(n)]）
+    pub endpoints: &'static [StaticEndpoint], // 路由（每个方法一项）
+    pub register: fn(&mut ServiceConfig),     // 把 handler 注册到所在的 scope
+}
+
+/// handler 的一条路由
+#[derive(Debug, Clone, Copy)]
+pub struct StaticEndpoint {
+    pub method: &'static str, // HTTP 方法（如 GET）
+    pub path: &'static str,   // handler 自身的路由路径（不含 scope）
+}
+
+inventory::collect!(StaticRoute);
+
+/// 所有已登记的 handler（顺序不确定）
+pub fn static_routes() -> impl Iterator<Item = &'static StaticRoute> {
+    inventory::iter::<StaticRoute>.into_iter()
+}
+
+/// 在 inventory 不支持的目标上报告编译错误，避免登记的 handler 在运行时悄悄丢失
+#[doc(hidden)]
+#[macro_export]
+macro_rules! assert_supported_target {
+    () => {
+        #[cfg(not(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "haiku",
+            target_os = "illumos",
+            target_os = "netbsd",
+            target_os = "openbsd",
+            target_os = "none",
+            target_os = "macos",
+            target_os = "ios",
+            target_family = "wasm",
+            windows
+        )))]
+        compile_error!(
+            "route_codegen static registration is not supported on this target; \
+             use the default registration = \"scan\" instead"
+        );
+    };
+}
+
+/// 登记 handler，由 `#[route_codegen::register]` 调用
+#[doc(hidden)]
+#[macro_export]
+macro_rules! submit {
+    ($route:expr) => {
+        $crate::assert_supported_target!();
+
+        $crate::registry::inventory::submit! { $route }
+    };
+}
+
+/// 静态注册生成的路由集合的选项，与扫描方式的宏参数含义相同
+#[derive(Debug)]
+pub struct Options {
+    pub crate_name: &'static str, // 调用宏的 crate（CARGO_CRATE_NAME），其 handler 使用 crate:: 路径
+    pub crate_names: &'static [(&'static str, &'static str)], // 依赖的库名 -> 调用宏的 crate 中引用它使用的名称
+    pub file_prefix: &'static str, // 调用宏的 crate 相对 workspace 根的目录，从 file!() 中去掉
+    pub flat: bool,                // scope = "flat"：不为模块生成 scope
+    pub prefix: &'static str,      // 所有路由的公共前缀
+    pub rules: ScopeRules<'static>, // 模块路径到 URL 前缀的映射规则
+}
+
+/// 一个 scope 中的 handler
+struct Group {
+    module_path: Vec<&'static str>, // 第一个模块的路径段（依赖 crate 以 crate 名称开头）
+    scope: String,                  // 完整前缀
+    routes: Vec<&'static StaticRoute>,
+}
+
+impl Options {
+    /// 按扫描方式的规则分组排序：先按模块计算前缀，前缀相同的模块合并，
+    /// 组内和组间的顺序与生成的 `configure` 相同
+    fn groups(&self) -> Vec<Group> {
+        let mut routes: Vec<&'static StaticRoute> = static_routes().collect();
+        // 与扫描时相同：同一模块中按声明位置排列
+        routes.sort_by(|a, b| {
+            (a.module_path, a.file, a.line, a.name).cmp(&(b.module_path, b.file, b.line, b.name))
+        });
+
+        // 与扫描时的分组键相同：本 crate 的模块路径不含 crate 名称，依赖 crate 的以 crate 名称开头
+        let mut by_module: BTreeMap<Vec<&'static str>, (Option<&'static str>, Vec<_>)> =
+            BTreeMap::new();
+        for route in routes {
+            let mut segments: Vec<&'static str> = route.module_path.split("::").collect();
+            segments[0] = self.dependency_name(segments[0]);
+            let dependency = Some(segments[0]).filter(|name| *name != self.crate_name);
+            let module_path = match dependency {
+                Some(_) => segments,
+                None => segments[1..].to_vec(),
+            };
+            by_module
+                .entry(module_path)
+                .or_insert_with(|| (dependency, Vec::new()))
+                .1
+                .push(route);
+        }
+
+        let mut by_scope: BTreeMap<String, Group> = BTreeMap::new();
+        for (module_path, (dependency, routes)) in by_module {
+            let scope = self.scope(dependency, &module_path);
+            by_scope
+                .entry(scope.clone())
+                .or_insert_with(|| Group {
+                    module_path,
+                    scope,
+                    routes: Vec::new(),
+                })
+                .routes
+                .extend(routes);
+        }
+
+        let mut groups: Vec<Group> = by_scope.into_values().collect();
+        for group in &mut groups {
+            group
+                .routes
+                .sort_by(|a, b| compare_handlers(&handler_order(a), &handler_order(b)));
+        }
+        groups.sort_by(|a, b| {
+            group_priority(b)
+                .cmp(&group_priority(a))
+                .then_with(|| compare_scopes(&a.scope, &b.scope))
+                .then_with(|| a.module_path.cmp(&b.module_path))
+        });
+        groups
+    }
+
+    /// module_path!() 以依赖的库名开头，重命名的依赖（`my_dep = { package = "dep-crate", .. }`）
+    /// 换成调用宏的 crate 中引用它使用的名称，与扫描方式相同
+    fn dependency_name(&self, lib_name: &'static str) -> &'static str {
+        self.crate_names
+            .iter()
+            .find(|(lib, _)| *lib == lib_name)
+            .map_or(lib_name, |(_, name)| name)
+    }
+
+    /// 分组内路由的完整前缀，根前缀为空字符串；依赖 crate 的模块路径以 crate 名称开头
+    fn scope(&self, dependency: Option<&str>, module_path: &[&str]) -> String {
+        let prefix = self.prefix.trim_end_matches('/');
+        if self.flat {
+            return prefix.to_string();
+        }
+        let module_path = match dependency {
+            Some(_) => module_path.get(1..).unwrap_or_default(),
+            None => module_path,
+        };
+        format!("{}{}", prefix, self.rules.scope(dependency, module_path))
+    }
+
+    /// 按注册顺序把所有登记的 handler 注册到 cfg
+    pub fn configure(&self, cfg: &mut ServiceConfig) {
+        let groups = self.groups();
+        if self.flat {
+            let prefix = self.prefix.trim_end_matches('/');
+            let register_all = move |cfg: &mut ServiceConfig| {
+                for group in &groups {
+                    for route in &group.routes {
+                        (route.register)(cfg);
+                    }
+                }
+            };
+            if prefix.is_empty() {
+                register_all(cfg);
+            } else {
+                cfg.service(web::scope(prefix).configure(register_all));
+            }
+            return;
+        }
+
+        for group in groups {
+            cfg.service(web::scope(&group.scope).configure(|cfg| {
+                for route in &group.routes {
+                    (route.register)(cfg);
+                }
+            }));
+        }
+    }
+
+    /// 按注册顺序展开的路由表，与扫描方式生成的 `ROUTES` 相同
+    fn route_infos(&self) -> Vec<RouteInfo> {
+        let mut infos = Vec::new();
+        for group in self.groups() {
+            for route in group.routes {
+                let (lib_name, module) = match route.module_path.split_once("::") {
+                    Some((lib_name, module)) => (lib_name, module),
+                    None => (route.module_path, ""),
+                };
+                let crate_name = self.dependency_name(lib_name);
+                let handler: &'static str = if crate_name == self.crate_name {
+                    leak(
+                        ["crate", module, route.name]
+                            .iter()
+                            .filter(|s| !s.is_empty())
+                            .copied()
+                            .collect::<Vec<_>>()
+                            .join("::"),
+                    )
+                } else {
+                    leak(
+                        [crate_name, module, route.name]
+                            .iter()
+                            .filter(|s| !s.is_empty())
+                            .copied()
+                            .collect::<Vec<_>>()
+                            .join("::"),
+                    )
+                };
+                for endpoint in route.endpoints {
+                    let path = leak(format!("{}{}", group.scope, endpoint.path));
+                    let params: Vec<&'static str> =
+                        path_params(path).into_iter().map(leak).collect();
+                    infos.push(RouteInfo {
+                        method: endpoint.method,
+                        path,
+                        handler,
+                        crate_name,
+                        module,
+                        file: relative_file(route.file, self.file_prefix),
+                        line: route.line,
+                        path_params: Vec::leak(params),
+                    });
+                }
+            }
+        }
+        infos
+    }
+}
+
+/// 静态注册生成的 `ROUTES`：第一次访问时根据登记的 handler 计算，之后与 `&[RouteInfo]` 用法相同
+pub struct Routes {
+    options: &'static Options,
+    routes: OnceLock<Vec<RouteInfo>>,
+}
+
+impl Routes {
+    pub const fn new(options: &'static Options) -> Self {
+        Self {
+            options,
+            routes: OnceLock::new(),
+        }
+    }
+}
+
+impl std::ops::Deref for Routes {
+    type Target = [RouteInfo];
+
+    fn deref(&self) -> &[RouteInfo] {
+        self.routes.get_or_init(|| self.options.route_infos())
+    }
+}
+
+fn handler_order(route: &StaticRoute) -> HandlerOrder<'static> {
+    HandlerOrder {
+        priority: route.priority,
+        path: route.endpoints.first().map(|e| e.path).unwrap_or(""),
+        name: route.name,
+    }
+}
+
+/// 分组的优先级：取组内 handler 的最大值
+fn group_priority(group: &Group) -> i32 {
+    group.routes.iter().map(|r| r.priority).max().unwrap_or(0)
+}
+
+/// file!() 相对 workspace 根，转换为与扫描结果相同的、相对调用宏的 crate 的路径
+fn relative_file(file: &'static str, prefix: &str) -> &'static str {
+    let file = if file.contains('\\') {
+        leak(file.replace('\\', "/"))
+    } else {
+        file
+    };
+    file.strip_prefix(prefix).unwrap_or(file)
+}
+
+/// 路由表只计算一次，其中的字符串在程序运行期间一直有效
+fn leak(s: String) -> &'static str {
+    Box::leak(s.into_boxed_str())
+}
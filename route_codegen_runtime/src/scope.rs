@@ -0,0 +1,89 @@
+//! 模块路径到 URL 前缀的映射，扫描生成的代码和静态注册在运行时使用同一套规则
+
+/// 模块路径到 URL 前缀的映射规则（`strip`、`collapse_duplicates`、`kebab_case`、`mount`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScopeRules<'a> {
+    pub strip: &'a [&'a str], // 去掉的开头模块（可以是 a::b 形式的多段路径）
+    pub collapse_duplicates: bool, // 合并重复的相邻段
+    pub kebab_case: bool,     // 模块名中的 _ 转换为 -
+    pub mount: &'a [(&'a str, &'a str)], // crate 名称 -> 挂载前缀
+}
+
+impl ScopeRules<'_> {
+    /// 计算模块对应的 URL 前缀，空字符串表示根
+    ///
+    /// `crate_name` 为依赖 crate 的名称（调用宏的 crate 自身为 None），
+    /// `module_path` 为该 crate 内的模块路径段。
+    pub fn scope<S: AsRef<str>>(&self, crate_name: Option<&str>, module_path: &[S]) -> String {
+        let mut segments: Vec<&str> = module_path.iter().map(|s| s.as_ref()).collect();
+
+        // 多条规则同时匹配时使用最长的一条
+        let matched = self
+            .strip
+            .iter()
+            .map(|rule| {
+                rule.split("::")
+                    .filter(|s| !s.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|rule| !rule.is_empty() && segments.starts_with(rule))
+            .max_by_key(|rule| rule.len());
+        if let Some(rule) = matched {
+            segments.drain(..rule.len());
+        }
+
+        let mount = match crate_name {
+            Some(name) => match self
+                .mount
+                .iter()
+                .find(|(crate_name, _)| *crate_name == name)
+            {
+                Some((_, prefix)) => prefix.to_string(),
+                None => format!("/{}", self.segment(name)),
+            },
+            None => String::new(),
+        };
+        self.relative_scope(&mount, &segments)
+    }
+
+    /// 在 `base` 后追加模块路径段（合并重复段、转换 kebab-case，不去掉开头的模块）
+    pub fn relative_scope<S: AsRef<str>>(&self, base: &str, module_path: &[S]) -> String {
+        let mut segments: Vec<&str> = module_path.iter().map(|s| s.as_ref()).collect();
+        if self.collapse_duplicates {
+            segments = collapse_duplicates(segments);
+        }
+
+        let mut scope = base.to_string();
+        for segment in segments {
+            scope.push('/');
+            scope.push_str(&self.segment(segment));
+        }
+        scope
+    }
+
+    /// 模块名转换为 URL 段
+    fn segment(&self, name: &str) -> String {
+        if self.kebab_case {
+            name.replace('_', "-")
+        } else {
+            name.to_string()
+        }
+    }
+}
+
+/// 相邻段中，后一段与前一段相同或以 `前一段_` 开头时去掉后一段（如 agency/agency_api）
+fn collapse_duplicates(segments: Vec<&str>) -> Vec<&str> {
+    let mut result: Vec<&str> = Vec::with_capacity(segments.len());
+    for segment in segments {
+        let duplicate = result.last().is_some_and(|previous| {
+            segment == *previous
+                || segment
+                    .strip_prefix(previous)
+                    .is_some_and(|rest| rest.starts_with('_'))
+        });
+        if !duplicate {
+            result.push(segment);
+        }
+    }
+    result
+}
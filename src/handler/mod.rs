@@ -7,5 +7,5 @@ pub mod submodules;
 
 #[put("/mod/{id}")]
 pub async fn update_mod(id: web::Path<String>) -> HttpResponse {
-    HttpResponse::Ok().body(format!("Updated user: {}", id.to_string()))
+    HttpResponse::Ok().body(format!("Updated user: {}", id))
 }
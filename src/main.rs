@@ -26,7 +26,7 @@ async fn main() -> std::io::Result<()> {
     }
 
     // 启动服务，并注册共享状态
-    HttpServer::new(move || App::new().service(health).configure(configure))
+    HttpServer::new(move || App::new().configure(configure))
         .bind("0.0.0.0:8080")?
         .run()
         .await